@@ -0,0 +1,139 @@
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use actix_web::body::{self, BodySize, BoxBody, MessageBody};
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderMap, CONTENT_LENGTH, CONTENT_TYPE, TRANSFER_ENCODING};
+use actix_web::http::StatusCode;
+use actix_web::{web, Error, HttpResponse};
+use swagger_ui::enforce::{self, Enforcer, Location, Rejection};
+
+/// Middleware validating requests and responses of the operations documented in the spec,
+/// see `swagger_ui::enforce`. Wrap the app or scope serving the API with it, requests to other
+/// routes pass through.
+///
+/// Bodies are buffered up to `Enforcer::body_limit`. Larger ones are rejected with `OnViolation::Reject`,
+/// and pass through unchecked, like streamed ones, with `OnViolation::Log`.
+#[derive(Clone)]
+pub struct Enforce {
+    enforcer: Arc<Enforcer>,
+}
+
+impl Enforce {
+    /// Create a new `Enforce` middleware validating with `enforcer`
+    pub fn new(enforcer: Enforcer) -> Self {
+        Self { enforcer: Arc::new(enforcer) }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Enforce
+    where S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+          B: MessageBody + 'static {
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Transform = EnforceMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(EnforceMiddleware { service: Rc::new(service), enforcer: self.enforcer.clone() }))
+    }
+}
+
+/// Service created by the `Enforce` middleware
+pub struct EnforceMiddleware<S> {
+    service: Rc<S>,
+    enforcer: Arc<Enforcer>,
+}
+
+impl<S, B> Service<ServiceRequest> for EnforceMiddleware<S>
+    where S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+          B: MessageBody + 'static {
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let enforcer = self.enforcer.clone();
+
+        Box::pin(async move {
+            let operation = match enforcer.operation(req.method().as_str(), req.path()) {
+                Some(operation) => operation,
+                None => return service.call(req).await.map(ServiceResponse::map_into_boxed_body),
+            };
+
+            let body = if enforcer.checks_body(request_body_size(req.headers())) {
+                match req.extract::<web::Payload>().await?.to_bytes_limited(enforcer.body_limit()).await {
+                    Ok(Ok(body)) => Some(body),
+                    Ok(Err(_)) => return Ok(req.into_response(HttpResponse::BadRequest().finish())),
+                    Err(_) => return Ok(req.into_response(rejected(enforcer.body_too_large(Location::RequestBody)))),
+                }
+            } else {
+                enforcer.skip_body(&operation, Location::RequestBody);
+                None
+            };
+            let checked = enforce::Request {
+                query: Some(req.query_string()).filter(|query| !query.is_empty()),
+                headers: headers(req.headers()),
+                body: body.as_deref(),
+            };
+            if let Err(rejection) = enforcer.enforce_request(&operation, &checked) {
+                return Ok(req.into_response(rejected(rejection)));
+            }
+            if let Some(body) = body {
+                req.set_payload(Payload::from(body));
+            }
+
+            let (request, response) = service.call(req).await?.into_parts();
+            let (response, body) = response.into_parts();
+            let size = match body.size() {
+                BodySize::None => Some(0),
+                BodySize::Sized(size) => Some(size),
+                BodySize::Stream => None,
+            };
+            if !enforcer.checks_body(size) {
+                enforcer.skip_body(&operation, Location::ResponseBody);
+                return Ok(ServiceResponse::new(request, response.set_body(BoxBody::new(body))));
+            }
+            let body = match body::to_bytes_limited(body, enforcer.body_limit()).await {
+                Ok(Ok(body)) => body,
+                Ok(Err(_)) => return Ok(ServiceResponse::new(request, HttpResponse::InternalServerError().finish())),
+                Err(_) => return Ok(ServiceResponse::new(request, rejected(enforcer.body_too_large(Location::ResponseBody)))),
+            };
+            let checked = enforce::Response {
+                status: response.status().as_u16(),
+                content_type: response.headers().get(CONTENT_TYPE).and_then(|value| value.to_str().ok()),
+                body: &body,
+            };
+            if let Err(rejection) = enforcer.enforce_response(&operation, &checked) {
+                return Ok(ServiceResponse::new(request, rejected(rejection)));
+            }
+            Ok(ServiceResponse::new(request, response.set_body(BoxBody::new(body))))
+        })
+    }
+}
+
+/// Size of the request body from its headers, `None` if it is sent in chunks
+fn request_body_size(headers: &HeaderMap) -> Option<u64> {
+    match headers.get(CONTENT_LENGTH) {
+        Some(length) => length.to_str().ok()?.parse().ok(),
+        None if headers.contains_key(TRANSFER_ENCODING) => None,
+        None => Some(0),
+    }
+}
+
+fn headers(headers: &HeaderMap) -> Vec<(&str, &str)> {
+    headers.iter()
+        .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)))
+        .collect()
+}
+
+fn rejected(rejection: Rejection) -> HttpResponse {
+    HttpResponse::build(StatusCode::from_u16(rejection.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR))
+        .content_type("application/json")
+        .body(rejection.body)
+}
@@ -1,35 +1,97 @@
+use std::sync::Arc;
 use actix_files::file_extension_to_mime;
 use actix_utils::future::{ok, ready};
-use actix_web::http::header::{ContentType, LOCATION};
+use actix_web::http::header::{ContentType, HeaderName, ACCESS_CONTROL_REQUEST_HEADERS, LOCATION, ORIGIN};
+use actix_web::http::Method;
 use actix_web::web::{self, ServiceConfig};
-use actix_web::{HttpRequest, HttpResponse, Route};
+use actix_web::{HttpRequest, HttpResponse, HttpResponseBuilder, Route};
 
-use swagger_ui::{Assets, Config, Spec};
+use swagger_ui::{Config, Cors, Options, SecurityHeaders, Spec, SpecOrUrl, Versions};
+use swagger_ui::renderer::Renderer;
+
+#[cfg(feature = "enforce")]
+mod enforce;
+#[cfg(feature = "enforce")]
+pub use enforce::{Enforce, EnforceMiddleware};
 
 const CONFIG_FILE_PATH: &str = "/swagger-ui-config.json";
 
 /// Returns a function which configures an `App` or a `Scope` to serve the swagger-ui page displaying the given `Spec`
 pub fn swagger(spec: Spec, config: Config) -> impl FnOnce(&mut ServiceConfig) {
+    swagger_with_options(spec, config, Options::default())
+}
+
+/// Same as `swagger`, with additional server-side options
+pub fn swagger_with_options(spec: Spec, config: Config, options: Options) -> impl FnOnce(&mut ServiceConfig) {
     let mut routes: Vec<(String, Route)> = vec![];
 
-    let config_route = config_route(config, spec.name.clone().into_owned());
+    let config_headers = options.security_headers.headers(CONFIG_FILE_PATH, None);
+    let spec_name = spec.name.clone().into_owned();
+    let configure = move |config: &mut Config, path: &str| config.url = format!("{}/{}", path, spec_name);
+    let config_route = config_route(config, configure, config_headers, options.cors.clone());
     routes.push(("/swagger-ui-config.json".to_owned(), config_route));
 
     let spec_path = spec.name.clone().into_owned();
-    let spec_route = spec_route(spec);
+    let spec_headers = options.security_headers.headers(&spec_path, None);
+    // registered first, the mocked spec takes precedence over the spec route
+    #[cfg(feature = "mock")]
+    if let Some(mock) = options.mock.clone() {
+        let mock = Arc::new(mock);
+        let spec_route = mock_spec_route(mock.clone(), spec_path.clone(), spec_headers.clone(), options.cors.clone());
+        routes.push((spec_path.clone(), spec_route));
+        routes.push((format!("/{}/{{path:.*}}", swagger_ui::mock::MOCK_PATH), mock_route(mock)));
+    }
+    let spec_route = spec_route(spec, spec_headers, options.cors.clone());
     routes.push((spec_path, spec_route));
 
     let index_route = index_route();
     routes.push(("".to_owned(), index_route));
 
-    for file in Assets::iter() {
-        let filename = file.as_ref();
-        let content_type = content_type(filename);
-        let content = Assets::get(filename).unwrap().into_owned();
+    #[cfg(feature = "proxy")]
+    if let Some(proxy) = options.proxy.clone() {
+        routes.push((format!("/{}", swagger_ui::proxy::PROXY_PATH), proxy_route(proxy)));
+    }
+
+    routes.push(("/{path:.*}".to_owned(), files_route(options)));
+
+    move |c| {
+        for (path, route) in routes {
+            c.route(path.as_str(), route);
+        }
+    }
+}
+
+/// Returns a function which configures an `App` or a `Scope` to serve the swagger-ui page displaying several versions
+/// of a spec, each at `{version}/{spec name}`, with a version selector, see `swagger_ui::versions`
+pub fn versioned(versions: Versions, config: Config) -> impl FnOnce(&mut ServiceConfig) {
+    versioned_with_options(versions, config, Options::default())
+}
+
+/// Same as `versioned`, with additional server-side options. Mock mode is not available for versions.
+pub fn versioned_with_options(versions: Versions, config: Config, options: Options) -> impl FnOnce(&mut ServiceConfig) {
+    let mut routes: Vec<(String, Route)> = vec![];
 
-        routes.push((format!("/{}", filename), body(content_type, content)));
+    for (version, spec) in versions.specs() {
+        let spec_path = format!("/{}", Versions::spec_path(version, spec));
+        let spec_headers = options.security_headers.headers(&spec_path, None);
+        let spec_route = spec_route(spec.clone(), spec_headers, options.cors.clone());
+        routes.push((spec_path, spec_route));
     }
 
+    let config_headers = options.security_headers.headers(CONFIG_FILE_PATH, None);
+    let configure = move |config: &mut Config, path: &str| *config = versions.config(config, &format!("{}/", path));
+    let config_route = config_route(config, configure, config_headers, options.cors.clone());
+    routes.push((CONFIG_FILE_PATH.to_owned(), config_route));
+
+    routes.push(("".to_owned(), index_route()));
+
+    #[cfg(feature = "proxy")]
+    if let Some(proxy) = options.proxy.clone() {
+        routes.push((format!("/{}", swagger_ui::proxy::PROXY_PATH), proxy_route(proxy)));
+    }
+
+    routes.push(("/{path:.*}".to_owned(), files_route(options)));
+
     move |c| {
         for (path, route) in routes {
             c.route(path.as_str(), route);
@@ -37,27 +99,246 @@ pub fn swagger(spec: Spec, config: Config) -> impl FnOnce(&mut ServiceConfig) {
     }
 }
 
-fn config_route(config: Config, spec_name: String) -> Route {
+/// Returns a function which configures an `App` or a `Scope` to render the given spec with another `Renderer`.
+/// Configure several scopes to render the same spec with several renderers.
+pub fn renderer(renderer: impl Renderer, spec: impl Into<SpecOrUrl>) -> impl FnOnce(&mut ServiceConfig) {
+    let renderer = Arc::new(renderer);
+    let mut routes: Vec<(String, Route)> = vec![];
+    let spec_url = match spec.into() {
+        SpecOrUrl::Spec(spec) => {
+            let spec_path = format!("/{}", spec.name.trim_start_matches('/'));
+            let spec_headers = SecurityHeaders::default().headers(&spec.name, None);
+            routes.push((spec_path.clone(), spec_route(spec, spec_headers, None)));
+            format!(".{}", spec_path)
+        }
+        SpecOrUrl::Url(url) => url.into_owned(),
+    };
+    routes.extend([
+        ("".to_owned(), redirect_route("index.html")),
+        ("/{path:.*}".to_owned(), web::to(move |path: web::Path<String>| {
+            let response = match renderer.file_response(&path, &spec_url) {
+                Some(file) => with_headers(HttpResponse::Ok(), &file.headers)
+                    .content_type(content_type(path.as_str()))
+                    .body(file.content),
+                None => HttpResponse::NotFound().finish(),
+            };
+            ready(response)
+        })),
+    ]);
+
+    move |c| {
+        for (path, route) in routes {
+            c.route(path.as_str(), route);
+        }
+    }
+}
+
+/// Returns a function which configures an `App` or a `Scope` to render the given spec with Redoc
+#[cfg(feature = "redoc")]
+pub fn redoc(spec: impl Into<SpecOrUrl>) -> impl FnOnce(&mut ServiceConfig) {
+    renderer(swagger_ui::renderer::Redoc, spec)
+}
+
+/// Returns a function which configures an `App` or a `Scope` to serve Swagger Editor preloaded with the given `Spec`.
+/// In dev mode (`Editor::saving_to`) the spec is read from and saved to its source file with `GET`/`PUT` requests.
+#[cfg(feature = "editor")]
+pub fn editor(editor: swagger_ui::editor::Editor, spec: Spec) -> impl FnOnce(&mut ServiceConfig) {
+    let spec_path = format!("/{}", spec.name.trim_start_matches('/'));
+    let spec_url = format!(".{}", spec_path);
+    let content_type = content_type(&spec.name);
+    let spec_headers = SecurityHeaders::default().headers(&spec.name, None);
+    let editor = Arc::new(editor);
+
+    let get_spec = {
+        let editor = editor.clone();
+        web::get().to(move || {
+            let response = match editor.spec_content(&spec) {
+                Ok(content) => with_headers(HttpResponse::Ok(), &spec_headers)
+                    .content_type(content_type.clone())
+                    .body(content),
+                Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+            };
+            ready(response)
+        })
+    };
+    let put_spec = {
+        let editor = editor.clone();
+        web::put().to(move |body: web::Bytes| ready(save_response(&editor, &body)))
+    };
+    let files = web::get().to(move |path: web::Path<String>| {
+        let response = match editor.file_response(&path, &spec_url) {
+            Some(file) => with_headers(HttpResponse::Ok(), &file.headers)
+                .content_type(self::content_type(path.as_str()))
+                .body(file.content),
+            None => HttpResponse::NotFound().finish(),
+        };
+        ready(response)
+    });
+
+    let routes: Vec<(String, Route)> = vec![
+        (spec_path.clone(), get_spec),
+        (spec_path, put_spec),
+        ("".to_owned(), redirect_route("index.html")),
+        ("/{path:.*}".to_owned(), files),
+    ];
+
+    move |c| {
+        for (path, route) in routes {
+            c.route(path.as_str(), route);
+        }
+    }
+}
+
+#[cfg(feature = "editor")]
+fn save_response(editor: &swagger_ui::editor::Editor, body: &[u8]) -> HttpResponse {
+    use swagger_ui::editor::SaveError;
+
+    match editor.save(body) {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(SaveError::ReadOnly) => HttpResponse::MethodNotAllowed().finish(),
+        Err(err @ SaveError::Parse(_)) => HttpResponse::BadRequest().body(err.to_string()),
+        Err(err @ SaveError::Io(_)) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+/// Serves `config` with the spec urls set by `configure`, from the path of the mount
+fn config_route(
+    config: Config,
+    configure: impl Fn(&mut Config, &str) + Clone + 'static,
+    headers: Vec<(&'static str, String)>,
+    cors: Option<Cors>,
+) -> Route {
     web::to(move |req: HttpRequest| {
+        if let Some(response) = preflight_response(cors.as_ref(), &req) {
+            return ready(response);
+        }
         let path = req.path().replace(CONFIG_FILE_PATH, "");
         let mut config = config.clone();
-        config.url = format!("{}/{}", path, &spec_name);
+        configure(&mut config, &path);
 
-        ready(HttpResponse::Ok().json(config))
+        let builder = with_headers(HttpResponse::Ok(), &headers);
+        ready(with_headers(builder, &cors_headers(cors.as_ref(), &req)).json(config))
     })
 }
 
-fn spec_route(spec: Spec) -> Route {
-    let content_type = content_type(&spec.name);
+fn spec_route(spec: Spec, headers: Vec<(&'static str, String)>, cors: Option<Cors>) -> Route {
     let content = spec.content;
+    content_route(content_type(&spec.name), move |_| content.clone(), headers, cors)
+}
+
+/// Serves the spec pointing "Try it out" to the mock routes
+#[cfg(feature = "mock")]
+fn mock_spec_route(mock: Arc<swagger_ui::mock::Mock>, spec_path: String, headers: Vec<(&'static str, String)>, cors: Option<Cors>) -> Route {
+    let content = move |req: &HttpRequest| {
+        let base = req.path().strip_suffix(spec_path.trim_start_matches('/')).unwrap_or("/");
+        mock.spec_content(&format!("{}{}", base, swagger_ui::mock::MOCK_PATH))
+    };
+    content_route(ContentType::json(), content, headers, cors)
+}
+
+/// Answers requests to the documented operations of the spec with mock responses
+#[cfg(feature = "mock")]
+fn mock_route(mock: Arc<swagger_ui::mock::Mock>) -> Route {
+    use actix_web::http::StatusCode;
+
+    web::to(move |req: HttpRequest, path: web::Path<String>| {
+        let response = match mock.respond(req.method().as_str(), &path) {
+            Some(mocked) => {
+                let mut builder = HttpResponse::build(StatusCode::from_u16(mocked.status).unwrap_or(StatusCode::OK));
+                if let Some(content_type) = mocked.content_type {
+                    builder.insert_header((actix_web::http::header::CONTENT_TYPE, content_type));
+                }
+                builder.body(mocked.body)
+            }
+            None => HttpResponse::NotFound().body("no documented operation"),
+        };
+        ready(response)
+    })
+}
 
-    web::to(move || {
-        ready(HttpResponse::Ok()
+fn content_route(
+    content_type: ContentType,
+    content: impl Fn(&HttpRequest) -> swagger_ui::Bytes + Clone + 'static,
+    headers: Vec<(&'static str, String)>,
+    cors: Option<Cors>,
+) -> Route {
+    web::to(move |req: HttpRequest| {
+        if let Some(response) = preflight_response(cors.as_ref(), &req) {
+            return ready(response);
+        }
+        let builder = with_headers(HttpResponse::Ok(), &headers);
+        ready(with_headers(builder, &cors_headers(cors.as_ref(), &req))
             .content_type(content_type.clone())
-            .body(content.clone()))
+            .body(content(&req)))
     })
 }
 
+/// Answers CORS preflight requests, if `cors` is set
+fn preflight_response(cors: Option<&Cors>, req: &HttpRequest) -> Option<HttpResponse> {
+    let cors = cors.filter(|_| req.method() == Method::OPTIONS)?;
+    let headers = cors.preflight_headers(header(req, ORIGIN), header(req, ACCESS_CONTROL_REQUEST_HEADERS));
+    Some(with_headers(HttpResponse::NoContent(), &headers).finish())
+}
+
+fn cors_headers(cors: Option<&Cors>, req: &HttpRequest) -> Vec<(&'static str, String)> {
+    cors.map(|cors| cors.headers(header(req, ORIGIN))).unwrap_or_default()
+}
+
+fn header(req: &HttpRequest, name: HeaderName) -> Option<&str> {
+    req.headers().get(name).and_then(|value| value.to_str().ok())
+}
+
+/// Forwards "Try it out" requests to their upstream
+#[cfg(feature = "proxy")]
+fn proxy_route(proxy: swagger_ui::proxy::Proxy) -> Route {
+    use actix_web::http::header::{HeaderName as Name, HeaderValue};
+    use actix_web::http::StatusCode;
+    use swagger_ui::proxy::{ProxyError, ProxyRequest};
+
+    let proxy = Arc::new(proxy);
+    // `web::Payload` rather than `web::Bytes`, whose default limit is lower than `Proxy::body_limit`
+    web::to(move |req: HttpRequest, payload: web::Payload| {
+        let proxy = proxy.clone();
+        async move {
+            let limit = usize::try_from(proxy.body_limit).unwrap_or(usize::MAX);
+            let body = match payload.to_bytes_limited(limit).await {
+                Ok(Ok(body)) => body,
+                Ok(Err(err)) => return HttpResponse::BadRequest().body(err.to_string()),
+                Err(_) => return HttpResponse::PayloadTooLarge().body(ProxyError::TooLarge.to_string()),
+            };
+            let request = ProxyRequest {
+                method: req.method().to_string(),
+                query: Some(req.query_string().to_owned()),
+                headers: req.headers().iter()
+                    .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+                    .collect(),
+                body,
+            };
+            match web::block(move || proxy.forward(request)).await {
+                Ok(Ok(upstream)) => {
+                    let mut builder = HttpResponse::build(StatusCode::from_u16(upstream.status).unwrap_or(StatusCode::BAD_GATEWAY));
+                    for (name, value) in upstream.headers {
+                        if let (Ok(name), Ok(value)) = (Name::from_bytes(name.as_bytes()), HeaderValue::from_str(&value)) {
+                            builder.append_header((name, value));
+                        }
+                    }
+                    builder.body(upstream.body)
+                }
+                Ok(Err(err)) => HttpResponse::build(StatusCode::from_u16(err.status()).unwrap_or(StatusCode::BAD_GATEWAY))
+                    .body(err.to_string()),
+                Err(_) => HttpResponse::InternalServerError().finish(),
+            }
+        }
+    })
+}
+
+fn with_headers(mut builder: HttpResponseBuilder, headers: &[(&'static str, String)]) -> HttpResponseBuilder {
+    for (name, value) in headers {
+        builder.insert_header((*name, value.as_str()));
+    }
+    builder
+}
+
 fn index_route() -> Route {
     web::to(|req: HttpRequest| {
         let path = req.path();
@@ -71,13 +352,26 @@ fn index_route() -> Route {
     })
 }
 
-fn body(content_type: ContentType, content: Vec<u8>) -> Route {
-    let handler = move || {
-        ok::<_, actix_web::Error>(
-            HttpResponse::Ok()
-                .content_type(content_type.clone())
-                .body(content.clone()),
-        )
+fn redirect_route(file: &'static str) -> Route {
+    web::to(move |req: HttpRequest| {
+        let location = format!("{}/{}", req.path().trim_end_matches('/'), file);
+
+        ready(HttpResponse::Found()
+            .append_header((LOCATION, location))
+            .finish())
+    })
+}
+
+fn files_route(options: Options) -> Route {
+    let options = Arc::new(options);
+    let handler = move |path: web::Path<String>| {
+        let response = match options.file_response(&path) {
+            Some(file) => with_headers(HttpResponse::Ok(), &file.headers)
+                .content_type(content_type(path.as_str()))
+                .body(file.content),
+            None => HttpResponse::NotFound().finish(),
+        };
+        ok::<_, actix_web::Error>(response)
     };
 
     web::to(handler)
@@ -104,7 +398,7 @@ mod tests {
     use actix_web::{ test::{TestRequest, call_service, init_service, read_body}, web::scope, App};
     use actix_web::dev::ServiceResponse;
     use actix_web::web::Bytes;
-    use swagger_ui::swagger_spec_file;
+    use swagger_ui::{swagger_spec_file, Overlay};
 
     use super::*;
 
@@ -161,4 +455,297 @@ mod tests {
 
         assert_eq!(body, expected_body);
     }
+
+    #[actix_rt::test]
+    async fn serves_overlay_files() {
+        let spec = swagger_spec_file!("../../swagger-ui/examples/openapi.json");
+        let mut options = Options::default();
+        options.overlay = Overlay::new().file("logo.svg", Bytes::from_static(b"<svg/>"));
+        let app = App::new()
+            .service(scope("/swagger-ui").configure(swagger_with_options(spec, Config::default(), options)));
+        let app = init_service(app).await;
+
+        let res = call_service(&app, get("/swagger-ui/logo.svg")).await;
+        assert!(res.status().is_success());
+        assert_eq!(read_body(res).await, Bytes::from_static(b"<svg/>"));
+
+        let res = call_service(&app, get("/swagger-ui/missing.js")).await;
+        assert_eq!(res.status(), actix_web::http::StatusCode::NOT_FOUND);
+    }
+
+    #[actix_rt::test]
+    async fn serves_versions() {
+        let spec = swagger_spec_file!("../../swagger-ui/examples/openapi.json");
+        let versions = Versions::new()
+            .with_version("v1", spec.clone())
+            .with_version("v2", spec);
+        let app = App::new()
+            .service(scope("/swagger-ui").configure(versioned(versions, Config::default())));
+        let app = init_service(app).await;
+
+        let res = call_service(&app, get("/swagger-ui/swagger-ui-config.json")).await;
+        let config: serde_json::Value = serde_json::from_slice(&read_body(res).await).unwrap();
+        assert_eq!(config["urls"][0], serde_json::json!({"name": "v2", "url": "/swagger-ui/v2/openapi.json"}));
+        assert_eq!(config["urls.primaryName"], "v2");
+
+        let res = call_service(&app, get("/swagger-ui/v1/openapi.json")).await;
+        assert!(res.status().is_success());
+
+        let res = call_service(&app, get("/swagger-ui/openapi.json")).await;
+        assert_eq!(res.status(), actix_web::http::StatusCode::NOT_FOUND);
+    }
+
+    struct Plain;
+
+    impl Renderer for Plain {
+        fn name(&self) -> &'static str {
+            "plain"
+        }
+
+        fn file(&self, path: &str, spec_url: &str) -> Option<swagger_ui::Bytes> {
+            (path == "index.html").then(|| swagger_ui::Bytes::from(format!("<a href=\"{}\">spec</a>", spec_url)))
+        }
+    }
+
+    #[actix_rt::test]
+    async fn serves_renderers() {
+        let spec = swagger_spec_file!("../../swagger-ui/examples/openapi.json");
+        let app = App::new()
+            .service(scope("/swagger-ui").configure(swagger(spec.clone(), Config::default())))
+            .service(scope("/plain").configure(renderer(Plain, spec)))
+            .service(scope("/remote").configure(renderer(Plain, "https://example.com/openapi.json")));
+        let app = init_service(app).await;
+
+        let res = call_service(&app, get("/plain")).await;
+        assert!(res.status().is_redirection());
+        assert!(has_location(&res, "/plain/index.html".to_owned()));
+
+        let res = call_service(&app, get("/plain/index.html")).await;
+        assert!(res.status().is_success());
+        assert_eq!(read_body(res).await, Bytes::from_static(br#"<a href="./openapi.json">spec</a>"#));
+
+        let res = call_service(&app, get("/plain/openapi.json")).await;
+        assert!(res.status().is_success());
+
+        let res = call_service(&app, get("/plain/missing.js")).await;
+        assert_eq!(res.status(), actix_web::http::StatusCode::NOT_FOUND);
+
+        let res = call_service(&app, get("/remote/index.html")).await;
+        assert_eq!(read_body(res).await, Bytes::from_static(br#"<a href="https://example.com/openapi.json">spec</a>"#));
+
+        let res = call_service(&app, get("/remote/openapi.json")).await;
+        assert_eq!(res.status(), actix_web::http::StatusCode::NOT_FOUND);
+    }
+
+    #[cfg(feature = "redoc")]
+    #[actix_rt::test]
+    async fn serves_redoc() {
+        let spec = swagger_spec_file!("../../swagger-ui/examples/openapi.json");
+        let app = App::new()
+            .service(scope("/redoc").configure(redoc(spec)));
+        let app = init_service(app).await;
+
+        let res = call_service(&app, get("/redoc")).await;
+        assert!(res.status().is_redirection());
+        assert!(has_location(&res, "/redoc/index.html".to_owned()));
+
+        let res = call_service(&app, get("/redoc/index.html")).await;
+        assert!(res.status().is_success());
+        let body = read_body(res).await;
+        assert!(std::str::from_utf8(&body).unwrap().contains(r#"spec-url="./openapi.json""#));
+
+        let res = call_service(&app, get("/redoc/redoc.standalone.js")).await;
+        assert!(res.status().is_success());
+
+        let res = call_service(&app, get("/redoc/openapi.json")).await;
+        assert!(res.status().is_success());
+    }
+
+    #[cfg(feature = "editor")]
+    #[actix_rt::test]
+    async fn serves_editor() {
+        use swagger_ui::editor::Editor;
+
+        let source = std::env::temp_dir().join(format!("actix-web-swagger-ui-editor-{}.yaml", std::process::id()));
+        fs::write(&source, "openapi: 3.0.0\n").unwrap();
+        let spec = Spec { name: "openapi.yaml".into(), content: Bytes::from_static(b"openapi: 3.0.0\n") };
+        let app = App::new()
+            .service(scope("/editor").configure(editor(Editor::saving_to(&source), spec)));
+        let app = init_service(app).await;
+
+        let res = call_service(&app, get("/editor/index.html")).await;
+        assert!(res.status().is_success());
+
+        let put = |uri: &str, body: &'static str| TestRequest::put().uri(uri).set_payload(body).to_request();
+        let res = call_service(&app, put("/editor/openapi.yaml", "openapi: [")).await;
+        assert_eq!(res.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        let res = call_service(&app, put("/editor/openapi.yaml", "openapi: 3.0.3\n")).await;
+        assert_eq!(res.status(), actix_web::http::StatusCode::NO_CONTENT);
+
+        let res = call_service(&app, get("/editor/openapi.yaml")).await;
+        assert_eq!(read_body(res).await, Bytes::from_static(b"openapi: 3.0.3\n"));
+        fs::remove_file(source).unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn sends_security_headers() {
+        let app = init_app!("/swagger-ui");
+
+        let res = call_service(&app, get("/swagger-ui/index.html")).await;
+        let csp = res.headers().get("content-security-policy").unwrap().to_str().unwrap().to_owned();
+        let nonce = csp.split("'nonce-").nth(1).unwrap().split('\'').next().unwrap().to_owned();
+        let body = read_body(res).await;
+        assert!(std::str::from_utf8(&body).unwrap().contains(&format!(r#"<script nonce="{}">"#, nonce)));
+
+        for uri in ["/swagger-ui/swagger-ui-bundle.js", "/swagger-ui/swagger-ui-config.json", "/swagger-ui/openapi.json"] {
+            let res = call_service(&app, get(uri)).await;
+            assert_eq!(res.headers().get("x-content-type-options").unwrap(), "nosniff");
+            assert!(res.headers().get("content-security-policy").is_none());
+        }
+    }
+
+    #[actix_rt::test]
+    async fn sends_cors_headers() {
+        let spec = swagger_spec_file!("../../swagger-ui/examples/openapi.json");
+        let mut options = Options::default();
+        options.cors = Some(Cors { max_age: Some(600), ..Cors::origins(["https://portal.example.com"]) });
+        let app = App::new()
+            .service(scope("/swagger-ui").configure(swagger_with_options(spec, Config::default(), options)));
+        let app = init_service(app).await;
+        let request = |request: TestRequest, uri: &str, origin: &str| request
+            .uri(uri)
+            .insert_header(("origin", origin))
+            .to_request();
+
+        for uri in ["/swagger-ui/swagger-ui-config.json", "/swagger-ui/openapi.json"] {
+            let res = call_service(&app, request(TestRequest::get(), uri, "https://portal.example.com")).await;
+            assert!(res.status().is_success());
+            assert_eq!(res.headers().get("access-control-allow-origin").unwrap(), "https://portal.example.com");
+            assert_eq!(res.headers().get("vary").unwrap(), "Origin");
+
+            let res = call_service(&app, request(TestRequest::default().method(Method::OPTIONS), uri, "https://portal.example.com")).await;
+            assert_eq!(res.status(), actix_web::http::StatusCode::NO_CONTENT);
+            assert_eq!(res.headers().get("access-control-max-age").unwrap(), "600");
+        }
+
+        let res = call_service(&app, request(TestRequest::get(), "/swagger-ui/openapi.json", "https://evil.example.com")).await;
+        assert!(res.headers().get("access-control-allow-origin").is_none());
+
+        let res = call_service(&app, request(TestRequest::get(), "/swagger-ui/index.html", "https://portal.example.com")).await;
+        assert!(res.headers().get("access-control-allow-origin").is_none());
+    }
+
+    #[cfg(feature = "proxy")]
+    #[actix_rt::test]
+    async fn proxies_requests() {
+        use std::io::{Read, Write};
+        use swagger_ui::proxy::Proxy;
+
+        // Stand-in upstream, answering one request with its request line
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let upstream = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![0; 4096];
+            let read = stream.read(&mut request).unwrap();
+            let line = String::from_utf8_lossy(&request[..read]).lines().next().unwrap_or_default().to_owned();
+            write!(stream, "HTTP/1.1 201 Created\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", line.len(), line).unwrap();
+        });
+        let spec = swagger_spec_file!("../../swagger-ui/examples/openapi.json");
+        let mut options = Options::default();
+        options.proxy = Some(Proxy { body_limit: 4 * 1024 * 1024, ..Proxy::new([format!("{}/api", upstream)]) });
+        let app = App::new()
+            .service(scope("/swagger-ui").configure(swagger_with_options(spec, Config::default(), options)));
+        let app = init_service(app).await;
+        let proxied = |url: &str| format!("/swagger-ui/proxy?url={}", url.replace(':', "%3A").replace('/', "%2F").replace('?', "%3F"));
+
+        let req = TestRequest::post().uri(&proxied(&format!("{}/api/pets?limit=1", upstream))).set_payload("{}").to_request();
+        let res = call_service(&app, req).await;
+        assert_eq!(res.status(), actix_web::http::StatusCode::CREATED);
+        assert_eq!(res.headers().get("content-type").unwrap(), "text/plain");
+        assert_eq!(read_body(res).await, Bytes::from_static(b"POST /api/pets?limit=1 HTTP/1.1"));
+
+        let res = call_service(&app, get(proxied(&format!("{}/admin", upstream)))).await;
+        assert_eq!(res.status(), actix_web::http::StatusCode::FORBIDDEN);
+
+        // Bodies are limited by `Proxy::body_limit`, not the default limit of actix-web
+        use actix_web::http::StatusCode;
+        for (size, status) in [(3 * 1024 * 1024, StatusCode::FORBIDDEN), (5 * 1024 * 1024, StatusCode::PAYLOAD_TOO_LARGE)] {
+            let req = TestRequest::post().uri(&proxied(&format!("{}/admin", upstream))).set_payload(vec![b'x'; size]).to_request();
+            assert_eq!(call_service(&app, req).await.status(), status);
+        }
+    }
+
+    #[cfg(feature = "mock")]
+    #[actix_rt::test]
+    async fn serves_mock() {
+        let spec = swagger_spec_file!("../../swagger-ui/examples/openapi.json");
+        let mut options = Options::default();
+        options.mock = Some(swagger_ui::mock::Mock::new(&spec).unwrap());
+        let app = App::new()
+            .service(scope("/docs").configure(swagger_with_options(spec, Config::default(), options)));
+        let app = init_service(app).await;
+
+        let res = call_service(&app, get("/docs/openapi.json")).await;
+        let spec: serde_json::Value = serde_json::from_slice(&read_body(res).await).unwrap();
+        assert_eq!(spec["basePath"], "/docs/mock");
+
+        let res = call_service(&app, get("/docs/mock/pet/42")).await;
+        assert!(res.status().is_success());
+        assert_eq!(res.headers().get("content-type").unwrap(), "application/json");
+        let pet: serde_json::Value = serde_json::from_slice(&read_body(res).await).unwrap();
+        assert_eq!(pet["photoUrls"], serde_json::json!(["string"]));
+
+        let res = call_service(&app, get("/docs/mock/owners")).await;
+        assert_eq!(res.status(), actix_web::http::StatusCode::NOT_FOUND);
+    }
+
+    #[cfg(feature = "enforce")]
+    #[actix_rt::test]
+    async fn enforces_spec() {
+        use actix_web::http::StatusCode;
+        use swagger_ui::enforce::{Enforcer, OnViolation};
+
+        let spec = swagger_spec_file!("../../swagger-ui/examples/openapi.json");
+        let enforcer = Enforcer::new(&spec, OnViolation::Reject).unwrap().with_body_limit(64);
+        let app = App::new()
+            .wrap(crate::Enforce::new(enforcer))
+            .route("/v2/pet/{id}", web::get().to(|| async { HttpResponse::Ok().json(serde_json::json!({"name": "Rex"})) }))
+            .route("/v2/pet", web::post().to(|| async { HttpResponse::MethodNotAllowed().finish() }))
+            .service(scope("/docs").configure(swagger(spec, Config::default())));
+        let app = init_service(app).await;
+
+        let res = call_service(&app, get("/v2/pet/abc")).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = serde_json::from_slice(&read_body(res).await).unwrap();
+        assert_eq!(body["violations"], serde_json::json!(["path parameter `petId`: expected integer"]));
+
+        let res = call_service(&app, get("/v2/pet/1")).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let req = TestRequest::post().uri("/v2/pet")
+            .insert_header(("content-type", "application/json"))
+            .set_payload(r#"{"name": "Rex", "photoUrls": []}"#)
+            .to_request();
+        let res = call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let post_pet = |body: String| TestRequest::post().uri("/v2/pet")
+            .insert_header(("content-type", "application/json"))
+            .set_payload(body)
+            .to_request();
+        let res = call_service(&app, post_pet("{}".to_owned())).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        // Bodies over the limit are rejected, chunked bodies are checked too
+        let res = call_service(&app, post_pet(format!(r#"{{"name": "{}"}}"#, "x".repeat(64)))).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let mut chunked = post_pet("{}".to_owned());
+        chunked.headers_mut().remove("content-length");
+        chunked.headers_mut().insert("transfer-encoding".parse().unwrap(), "chunked".parse().unwrap());
+        let res = call_service(&app, chunked).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let res = call_service(&app, get("/docs/index.html")).await;
+        assert!(res.status().is_success());
+    }
 }
@@ -0,0 +1,118 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use axum::body::{self, Body, Full, HttpBody};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{HeaderMap, Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use http_body::{LengthLimitError, Limited};
+use swagger_ui::enforce::{self, Enforcer, Location, Rejection};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Tower layer validating requests and responses of the operations documented in the spec,
+/// see `swagger_ui::enforce`. Add it to the router serving the API, requests to other
+/// routes pass through.
+///
+/// Bodies are buffered up to `Enforcer::body_limit`. Larger ones are rejected with `OnViolation::Reject`,
+/// and pass through unchecked, like streamed ones, with `OnViolation::Log`.
+#[derive(Clone)]
+pub struct EnforceLayer {
+    enforcer: Arc<Enforcer>,
+}
+
+impl EnforceLayer {
+    /// Create a new `EnforceLayer` validating with `enforcer`
+    pub fn new(enforcer: Enforcer) -> Self {
+        Self { enforcer: Arc::new(enforcer) }
+    }
+}
+
+impl<S> Layer<S> for EnforceLayer {
+    type Service = Enforce<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Enforce { inner, enforcer: self.enforcer.clone() }
+    }
+}
+
+/// Service created by `EnforceLayer`
+#[derive(Clone)]
+pub struct Enforce<S> {
+    inner: S,
+    enforcer: Arc<Enforcer>,
+}
+
+impl<S> Service<Request<Body>> for Enforce<S>
+    where S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+          S::Future: Send + 'static {
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // The service polled ready is the one to call, its clone takes its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let enforcer = self.enforcer.clone();
+        let operation = match enforcer.operation(request.method().as_str(), request.uri().path()) {
+            Some(operation) => operation,
+            None => return Box::pin(inner.call(request)),
+        };
+
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let (body, checked_body) = if enforcer.checks_body(body.size_hint().exact()) {
+                match hyper::body::to_bytes(Limited::new(body, enforcer.body_limit())).await {
+                    Ok(bytes) => (Body::from(bytes.clone()), Some(bytes)),
+                    Err(err) if err.is::<LengthLimitError>() => return Ok(rejected(enforcer.body_too_large(Location::RequestBody))),
+                    Err(_) => return Ok(StatusCode::BAD_REQUEST.into_response()),
+                }
+            } else {
+                enforcer.skip_body(&operation, Location::RequestBody);
+                (body, None)
+            };
+            let checked = enforce::Request { query: parts.uri.query(), headers: headers(&parts.headers), body: checked_body.as_deref() };
+            if let Err(rejection) = enforcer.enforce_request(&operation, &checked) {
+                return Ok(rejected(rejection));
+            }
+
+            let response = inner.call(Request::from_parts(parts, body)).await?;
+            let (parts, body) = response.into_parts();
+            if !enforcer.checks_body(body.size_hint().exact()) {
+                enforcer.skip_body(&operation, Location::ResponseBody);
+                return Ok(Response::from_parts(parts, body));
+            }
+            let body = match hyper::body::to_bytes(Limited::new(body, enforcer.body_limit())).await {
+                Ok(body) => body,
+                Err(err) if err.is::<LengthLimitError>() => return Ok(rejected(enforcer.body_too_large(Location::ResponseBody))),
+                Err(_) => return Ok(StatusCode::INTERNAL_SERVER_ERROR.into_response()),
+            };
+            let checked = enforce::Response {
+                status: parts.status.as_u16(),
+                content_type: parts.headers.get(CONTENT_TYPE).and_then(|value| value.to_str().ok()),
+                body: &body,
+            };
+            if let Err(rejection) = enforcer.enforce_response(&operation, &checked) {
+                return Ok(rejected(rejection));
+            }
+            Ok(Response::from_parts(parts, body::boxed(Full::from(body))))
+        })
+    }
+}
+
+fn headers(headers: &HeaderMap) -> Vec<(&str, &str)> {
+    headers.iter()
+        .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)))
+        .collect()
+}
+
+fn rejected(rejection: Rejection) -> Response {
+    let status = StatusCode::from_u16(rejection.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    (status, [(CONTENT_TYPE, "application/json")], rejection.body).into_response()
+}
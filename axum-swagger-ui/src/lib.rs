@@ -1,40 +1,231 @@
 use std::sync::Arc;
 use axum::extract::OriginalUri;
-use axum::http::{StatusCode, Uri};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode, Uri};
+use axum::http::header::{ACCESS_CONTROL_REQUEST_HEADERS, ORIGIN};
 use axum::response::{IntoResponse, Redirect, Response};
 use axum::{Json, Router, TypedHeader};
 use axum::headers::ContentType;
 use axum::routing::get;
-use swagger_ui::{Assets, Config, SpecOrUrl};
+use swagger_ui::{Config, Options, SecurityHeaders, Spec, SpecOrUrl, Versions};
+use swagger_ui::renderer::Renderer;
+
+#[cfg(feature = "enforce")]
+mod enforce;
+#[cfg(feature = "enforce")]
+pub use enforce::{Enforce, EnforceLayer};
 
 /// Helper trait to allow `route.swagger_ui_route(...)`
 pub trait SwaggerUiExt {
     fn swagger_ui(self, path: &str, spec: impl Into<SpecOrUrl>, config: impl Into<Option<Config>>) -> Self;
+    fn swagger_ui_with_options(self, path: &str, spec: impl Into<SpecOrUrl>, config: impl Into<Option<Config>>, options: Options) -> Self;
 }
 
 impl SwaggerUiExt for Router {
     fn swagger_ui(self, path: &str, spec: impl Into<SpecOrUrl>, config: impl Into<Option<Config>>) -> Self {
         self.nest(path, swagger_ui_route(spec, config))
     }
+
+    fn swagger_ui_with_options(self, path: &str, spec: impl Into<SpecOrUrl>, config: impl Into<Option<Config>>, options: Options) -> Self {
+        self.nest(path, swagger_ui_route_with_options(spec, config, options))
+    }
 }
 
 /// creates a route that is configured to serve the specified spec and config with swagger_ui
 pub fn swagger_ui_route(spec: impl Into<SpecOrUrl>, config: impl Into<Option<Config>>) -> Router {
-    let config = Arc::new(config.into().unwrap_or_default());
+    swagger_ui_route_with_options(spec, config, Options::default())
+}
+
+/// same as `swagger_ui_route`, with additional server-side options
+pub fn swagger_ui_route_with_options(spec: impl Into<SpecOrUrl>, config: impl Into<Option<Config>>, options: Options) -> Router {
+    mount_route(Mount::new(Specs::One(spec.into()), config.into().unwrap_or_default(), options))
+}
+
+/// creates a route that serves the swagger-ui page displaying several versions of a spec,
+/// each at `{version}/{spec name}`, with a version selector, see `swagger_ui::versions`
+pub fn versioned_route(versions: Versions, config: impl Into<Option<Config>>) -> Router {
+    versioned_route_with_options(versions, config, Options::default())
+}
+
+/// same as `versioned_route`, with additional server-side options. Mock mode is not available for versions.
+pub fn versioned_route_with_options(versions: Versions, config: impl Into<Option<Config>>, options: Options) -> Router {
+    mount_route(Mount::new(Specs::Versions(versions), config.into().unwrap_or_default(), options))
+}
+
+fn mount_route(mount: Mount) -> Router {
+    let mount = Arc::new(mount);
+    let preflight_mount = mount.clone();
+    #[cfg(feature = "proxy")]
+    let proxy = mount.options.proxy.clone().map(Arc::new);
+    #[cfg(feature = "mock")]
+    let mock = mount.mock.clone();
+    let router = Router::new()
+        .route("/", get(redirect_index))
+        .route("/*path",
+               get(move |uri: Uri, original: OriginalUri, headers: HeaderMap| {
+                   let mount = mount.clone();
+                   async move {
+                       handle_path(uri, original, &headers, &mount).await
+                   }
+               })
+               .options(move |uri: Uri, headers: HeaderMap| {
+                   let mount = preflight_mount.clone();
+                   async move {
+                       handle_preflight(uri, &headers, &mount)
+                   }
+               }),
+        );
+    #[cfg(feature = "proxy")]
+    let router = match proxy {
+        // The `Bytes` extractor is otherwise limited to 2 MB, below `Proxy::body_limit`
+        Some(proxy) => router.route(&format!("/{}", swagger_ui::proxy::PROXY_PATH),
+                                    axum::routing::any({
+                                        let proxy = proxy.clone();
+                                        move |method: axum::http::Method, uri: Uri, headers: HeaderMap, body: axum::body::Bytes| {
+                                            handle_proxy(proxy.clone(), method, uri, headers, body)
+                                        }
+                                    }).layer(axum::extract::DefaultBodyLimit::max(usize::try_from(proxy.body_limit).unwrap_or(usize::MAX))),
+        ),
+        None => router,
+    };
+    #[cfg(feature = "mock")]
+    let router = match mock {
+        Some(mock) => router.route(&format!("/{}/*path", swagger_ui::mock::MOCK_PATH),
+                                   axum::routing::any(move |method: axum::http::Method, uri: Uri| {
+                                       let mock = mock.clone();
+                                       async move {
+                                           handle_mock(&mock, &method, &uri)
+                                       }
+                                   }),
+        ),
+        None => router,
+    };
+    router
+}
+
+/// Spec(s), config and options served by a swagger-ui route
+struct Mount {
+    spec: Specs,
+    config: Config,
+    options: Options,
+    #[cfg(feature = "mock")]
+    mock: Option<Arc<swagger_ui::mock::Mock>>,
+}
+
+impl Mount {
+    fn new(spec: Specs, config: Config, options: Options) -> Self {
+        Self {
+            #[cfg(feature = "mock")]
+            mock: match &spec {
+                Specs::One(SpecOrUrl::Spec(_)) => options.mock.clone().map(Arc::new),
+                _ => None,
+            },
+            spec,
+            config,
+            options,
+        }
+    }
+}
+
+/// A single spec or url, or several versions of a spec
+enum Specs {
+    One(SpecOrUrl),
+    Versions(Versions),
+}
+
+impl Specs {
+    /// Returns the spec served at `path`, if any
+    fn spec(&self, path: &str) -> Option<&Spec> {
+        match self {
+            Specs::One(SpecOrUrl::Spec(spec)) if path == spec.name.trim_start_matches("/") => Some(spec),
+            Specs::One(_) => None,
+            Specs::Versions(versions) => versions.spec(path),
+        }
+    }
+}
+
+/// creates a route that renders the specified spec with another `Renderer`, to be nested next to the swagger_ui one.
+/// Nest it several times to render the same spec with several renderers.
+pub fn renderer_route(renderer: impl Renderer, spec: impl Into<SpecOrUrl>) -> Router {
+    let renderer: Arc<dyn Renderer> = Arc::new(renderer);
     let spec = Arc::new(spec.into());
     Router::new()
         .route("/", get(redirect_index))
         .route("/*path",
-               get(move |uri: Uri, original: OriginalUri| {
-                   let config = config.clone();
+               get(move |uri: Uri| {
+                   let renderer = renderer.clone();
+                   let spec = spec.clone();
+                   async move {
+                       handle_renderer_path(uri, renderer.as_ref(), &spec)
+                   }
+               }),
+        )
+}
+
+/// creates a route that renders the specified spec with Redoc, to be nested next to the swagger_ui one
+#[cfg(feature = "redoc")]
+pub fn redoc_route(spec: impl Into<SpecOrUrl>) -> Router {
+    renderer_route(swagger_ui::renderer::Redoc, spec)
+}
+
+/// creates a route that serves Swagger Editor preloaded with the specified spec.
+/// In dev mode (`Editor::saving_to`) the spec is read from and saved to its source file with `GET`/`PUT` requests.
+#[cfg(feature = "editor")]
+pub fn editor_route(editor: swagger_ui::editor::Editor, spec: swagger_ui::Spec) -> Router {
+    let spec_path = format!("/{}", spec.name.trim_start_matches("/"));
+    let spec_url = format!(".{}", spec_path);
+    let editor = Arc::new(editor);
+    let spec = Arc::new(spec);
+    let (get_editor, put_editor, files_editor) = (editor.clone(), editor.clone(), editor);
+    Router::new()
+        .route("/", get(redirect_index))
+        .route(&spec_path,
+               get(move || {
+                   let editor = get_editor.clone();
                    let spec = spec.clone();
                    async move {
-                       handle_path(uri, original, &spec, &config).await
+                       match editor.spec_content(&spec) {
+                           Ok(content) => {
+                               let headers = SecurityHeaders::default().headers(&spec.name, None);
+                               with_headers((mime_type(&spec.name), content).into_response(), &headers)
+                           }
+                           Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+                       }
+                   }
+               })
+               .put(move |body: axum::body::Bytes| {
+                   let editor = put_editor.clone();
+                   async move {
+                       handle_editor_save(&editor, &body)
+                   }
+               }),
+        )
+        .route("/*path",
+               get(move |uri: Uri| {
+                   let editor = files_editor.clone();
+                   let spec_url = spec_url.clone();
+                   async move {
+                       let path = uri.path().trim_start_matches("/");
+                       match editor.file_response(path, &spec_url) {
+                           Some(file) => with_headers((mime_type(path), file.content).into_response(), &file.headers),
+                           None => StatusCode::NOT_FOUND.into_response(),
+                       }
                    }
                }),
         )
 }
 
+#[cfg(feature = "editor")]
+fn handle_editor_save(editor: &swagger_ui::editor::Editor, body: &[u8]) -> Response {
+    use swagger_ui::editor::SaveError;
+
+    match editor.save(body) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(SaveError::ReadOnly) => StatusCode::METHOD_NOT_ALLOWED.into_response(),
+        Err(err @ SaveError::Parse(_)) => (StatusCode::BAD_REQUEST, err.to_string()).into_response(),
+        Err(err @ SaveError::Io(_)) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
+
 async fn redirect_index(uri: OriginalUri) -> Redirect {
     let p = uri.path().trim_end_matches("/");
     let query = uri.query();
@@ -49,28 +240,136 @@ fn mime_type(filename: &str) -> TypedHeader<ContentType> {
     TypedHeader(ContentType::from(mime_guess::from_ext(filename.split(".").last().unwrap_or_default()).first_or_octet_stream()))
 }
 
-async fn handle_path(uri: Uri, original: OriginalUri, spec: &SpecOrUrl, config: &Config) -> Response {
+async fn handle_path(uri: Uri, original: OriginalUri, request_headers: &HeaderMap, mount: &Mount) -> Response {
+    let (spec, options) = (&mount.spec, &mount.options);
     let path = uri.path().trim_start_matches("/");
-    if let Some(asset) = Assets::get(path) {
+    if let Some(file) = options.file_response(path) {
         let t = mime_type(path);
-        return (t, asset).into_response();
+        return with_headers((t, file.content).into_response(), &file.headers);
+    }
+    let mut headers = options.security_headers.headers(path, None);
+    if let Some(cors) = &options.cors {
+        headers.extend(cors.headers(header(request_headers, ORIGIN)));
     }
     if path == "swagger-ui-config.json" {
-        let mut config = config.clone();
+        let mut config = mount.config.clone();
         match spec {
-            SpecOrUrl::Spec(spec) => config.url = original.path().replace("swagger-ui-config.json", &spec.name),
-            SpecOrUrl::Url(url) => config.url = url.to_string()
+            Specs::One(SpecOrUrl::Spec(spec)) => config.url = original.path().replace("swagger-ui-config.json", &spec.name),
+            Specs::One(SpecOrUrl::Url(url)) => config.url = url.to_string(),
+            Specs::Versions(versions) => config = versions.config(&config, original.path().strip_suffix(path).unwrap_or("/")),
         }
-        return Json(config).into_response();
+        return with_headers(Json(config).into_response(), &headers);
     }
-    if let SpecOrUrl::Spec(spec) = spec {
-        if path == spec.name.trim_start_matches("/") {
-            return (TypedHeader(ContentType::json()), spec.content.clone()).into_response();
+    if let Some(spec) = spec.spec(path) {
+        #[cfg(feature = "mock")]
+        if let Some(mock) = &mount.mock {
+            let mock_url = format!("{}{}", original.path().strip_suffix(path).unwrap_or("/"), swagger_ui::mock::MOCK_PATH);
+            return with_headers((TypedHeader(ContentType::json()), mock.spec_content(&mock_url)).into_response(), &headers);
         }
+        return with_headers(spec_response(spec), &headers);
     }
     StatusCode::NOT_FOUND.into_response()
 }
 
+/// Answers a request to the documented operations of the spec with a mock response
+#[cfg(feature = "mock")]
+fn handle_mock(mock: &swagger_ui::mock::Mock, method: &axum::http::Method, uri: &Uri) -> Response {
+    use axum::http::header::CONTENT_TYPE;
+
+    let path = uri.path().strip_prefix(&format!("/{}", swagger_ui::mock::MOCK_PATH)).unwrap_or_default();
+    match mock.respond(method.as_str(), path) {
+        Some(mocked) => {
+            let mut response = (StatusCode::from_u16(mocked.status).unwrap_or(StatusCode::OK), mocked.body).into_response();
+            match mocked.content_type.as_deref().map(HeaderValue::from_str) {
+                Some(Ok(content_type)) => response.headers_mut().insert(CONTENT_TYPE, content_type),
+                _ => response.headers_mut().remove(CONTENT_TYPE),
+            };
+            response
+        }
+        None => (StatusCode::NOT_FOUND, "no documented operation").into_response(),
+    }
+}
+
+/// Forwards a "Try it out" request to its upstream
+#[cfg(feature = "proxy")]
+async fn handle_proxy(proxy: Arc<swagger_ui::proxy::Proxy>, method: axum::http::Method, uri: Uri, headers: HeaderMap, body: axum::body::Bytes) -> Response {
+    use axum::http::header::CONTENT_TYPE;
+    use swagger_ui::proxy::ProxyRequest;
+
+    let request = ProxyRequest {
+        method: method.to_string(),
+        query: uri.query().map(str::to_owned),
+        headers: headers.iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+            .collect(),
+        body,
+    };
+    match tokio::task::spawn_blocking(move || proxy.forward(request)).await {
+        Ok(Ok(upstream)) => {
+            let status = StatusCode::from_u16(upstream.status).unwrap_or(StatusCode::BAD_GATEWAY);
+            let mut response = (status, upstream.body).into_response();
+            response.headers_mut().remove(CONTENT_TYPE);
+            for (name, value) in upstream.headers {
+                if let (Ok(name), Ok(value)) = (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(&value)) {
+                    response.headers_mut().append(name, value);
+                }
+            }
+            response
+        }
+        Ok(Err(err)) => (StatusCode::from_u16(err.status()).unwrap_or(StatusCode::BAD_GATEWAY), err.to_string()).into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+/// Answers CORS preflight requests for the config and spec
+fn handle_preflight(uri: Uri, request_headers: &HeaderMap, mount: &Mount) -> Response {
+    let (spec, options) = (&mount.spec, &mount.options);
+    let path = uri.path().trim_start_matches("/");
+    let cors_path = path == "swagger-ui-config.json" || spec.spec(path).is_some();
+    match &options.cors {
+        Some(cors) if cors_path => {
+            let headers = cors.preflight_headers(
+                header(request_headers, ORIGIN),
+                header(request_headers, ACCESS_CONTROL_REQUEST_HEADERS),
+            );
+            with_headers(StatusCode::NO_CONTENT.into_response(), &headers)
+        }
+        _ => StatusCode::METHOD_NOT_ALLOWED.into_response(),
+    }
+}
+
+fn header(headers: &HeaderMap, name: HeaderName) -> Option<&str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn with_headers(mut response: Response, headers: &[(&'static str, String)]) -> Response {
+    for (name, value) in headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+            response.headers_mut().insert(name, value);
+        }
+    }
+    response
+}
+
+fn handle_renderer_path(uri: Uri, renderer: &dyn Renderer, spec: &SpecOrUrl) -> Response {
+    let path = uri.path().trim_start_matches("/");
+    let spec_url = match spec {
+        SpecOrUrl::Spec(spec) if path == spec.name.trim_start_matches("/") => {
+            return with_headers(spec_response(spec), &SecurityHeaders::default().headers(path, None));
+        }
+        SpecOrUrl::Spec(spec) => format!("./{}", spec.name.trim_start_matches("/")),
+        SpecOrUrl::Url(url) => url.to_string(),
+    };
+    match renderer.file_response(path, &spec_url) {
+        Some(file) => with_headers((mime_type(path), file.content).into_response(), &file.headers),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+fn spec_response(spec: &Spec) -> Response {
+    (TypedHeader(ContentType::json()), spec.content.clone()).into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use axum::body::Body;
@@ -81,13 +380,23 @@ mod tests {
     use hyper::Method;
     use tower::Service;
     use tower::ServiceExt;
-    use swagger_ui::Config;
-    use crate::{swagger_ui_route};
+    use swagger_ui::{Bytes, Config, Options, Overlay};
+    use crate::{swagger_ui_route, swagger_ui_route_with_options};
 
     fn app() -> Router {
         swagger_ui_route(swagger_ui::swagger_spec_file!("../../swagger-ui/examples/openapi.json"), None)
     }
 
+    fn app_with_options(options: Options) -> Router {
+        swagger_ui_route_with_options(swagger_ui::swagger_spec_file!("../../swagger-ui/examples/openapi.json"), None, options)
+    }
+
+    async fn get(app: Router, uri: &str) -> hyper::Response<axum::body::BoxBody> {
+        app.oneshot(Request::builder().method(Method::GET).uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
     #[tokio::test]
     async fn does_redirect() {
         let app = app();
@@ -129,4 +438,335 @@ mod tests {
         let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
         let config: Config = serde_json::from_str(std::str::from_utf8(body.as_ref()).unwrap()).unwrap();
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn does_overlay() {
+        let mut options = Options::default();
+        options.overlay = Overlay::new().file("logo.svg", Bytes::from_static(b"<svg/>"));
+
+        let response = get(app_with_options(options), "/logo.svg").await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(CONTENT_TYPE).unwrap(), "image/svg+xml");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body.as_ref(), b"<svg/>");
+    }
+
+    #[tokio::test]
+    async fn does_theme() {
+        let mut options = Options::default();
+        options.theme.title = Some("Pet store".into());
+        options.theme.css = Some(".swagger-ui { color: red; }".into());
+        let app = app_with_options(options);
+
+        let response = get(app.clone(), "/index.html").await;
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(std::str::from_utf8(&body).unwrap().contains("<title>Pet store</title>"));
+
+        let response = get(app, "/index.css").await;
+        assert_eq!(response.headers().get(CONTENT_TYPE).unwrap(), "text/css");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(std::str::from_utf8(&body).unwrap().contains(".swagger-ui { color: red; }"));
+    }
+
+    struct Plain;
+
+    impl swagger_ui::renderer::Renderer for Plain {
+        fn name(&self) -> &'static str {
+            "plain"
+        }
+
+        fn file(&self, path: &str, spec_url: &str) -> Option<Bytes> {
+            (path == "index.html").then(|| Bytes::from(format!("<a href=\"{}\">spec</a>", spec_url)))
+        }
+    }
+
+    #[tokio::test]
+    async fn does_renderers() {
+        let spec = swagger_ui::swagger_spec_file!("../../swagger-ui/examples/openapi.json");
+        let app = Router::new()
+            .nest("/docs/swagger", app())
+            .nest("/docs/plain", crate::renderer_route(Plain, spec.clone()))
+            .nest("/docs/remote", crate::renderer_route(Plain, "https://example.com/openapi.json"));
+
+        let response = get(app.clone(), "/docs/plain").await;
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+
+        let response = get(app.clone(), "/docs/plain/index.html").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body.as_ref(), br#"<a href="./openapi.json">spec</a>"#);
+
+        let response = get(app.clone(), "/docs/remote/index.html").await;
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body.as_ref(), br#"<a href="https://example.com/openapi.json">spec</a>"#);
+
+        let response = get(app.clone(), "/docs/plain/openapi.json").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = get(app, "/docs/plain/missing.js").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn does_versions() {
+        let spec = swagger_ui::swagger_spec_file!("../../swagger-ui/examples/openapi.json");
+        let versions = swagger_ui::Versions::new()
+            .with_version("v1", spec.clone())
+            .with_version("v2", spec);
+        let app = Router::new().nest("/docs", crate::versioned_route(versions, None));
+
+        let response = get(app.clone(), "/docs/swagger-ui-config.json").await;
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let config: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(config["urls"], serde_json::json!([
+            {"name": "v2", "url": "/docs/v2/openapi.json"},
+            {"name": "v1", "url": "/docs/v1/openapi.json"},
+        ]));
+        assert_eq!(config["urls.primaryName"], "v2");
+
+        let response = get(app.clone(), "/docs/v1/openapi.json").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = get(app, "/docs/openapi.json").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[cfg(feature = "redoc")]
+    #[tokio::test]
+    async fn does_redoc() {
+        let app = Router::new()
+            .nest("/docs/swagger", app())
+            .nest("/docs/redoc", crate::redoc_route(swagger_ui::swagger_spec_file!("../../swagger-ui/examples/openapi.json")));
+
+        let response = get(app.clone(), "/docs/redoc").await;
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+
+        let response = get(app.clone(), "/docs/redoc/index.html").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(std::str::from_utf8(&body).unwrap().contains(r#"spec-url="./openapi.json""#));
+
+        let response = get(app.clone(), "/docs/redoc/redoc.standalone.js").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = get(app, "/docs/redoc/openapi.json").await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[cfg(feature = "editor")]
+    #[tokio::test]
+    async fn does_editor() {
+        use swagger_ui::editor::Editor;
+
+        let source = std::env::temp_dir().join(format!("axum-swagger-ui-editor-{}.yaml", std::process::id()));
+        std::fs::write(&source, "openapi: 3.0.0\n").unwrap();
+        let spec = swagger_ui::Spec { name: "openapi.yaml".into(), content: Bytes::from_static(b"openapi: 3.0.0\n") };
+        let app = Router::new()
+            .nest("/editor", crate::editor_route(Editor::saving_to(&source), spec.clone()))
+            .nest("/readonly", crate::editor_route(Editor::new(), spec));
+
+        let response = get(app.clone(), "/editor/index.html").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let put = |uri: &str, body: &'static str| Request::builder().method(Method::PUT).uri(uri).body(Body::from(body)).unwrap();
+        let response = app.clone().oneshot(put("/editor/openapi.yaml", "openapi: [")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let response = app.clone().oneshot(put("/editor/openapi.yaml", "openapi: 3.0.3\n")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = app.clone().oneshot(put("/readonly/openapi.yaml", "openapi: 3.0.3\n")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let response = get(app, "/editor/openapi.yaml").await;
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body.as_ref(), b"openapi: 3.0.3\n");
+        std::fs::remove_file(source).unwrap();
+    }
+
+    #[tokio::test]
+    async fn does_security_headers() {
+        let app = app();
+
+        let response = get(app.clone(), "/index.html").await;
+        let csp = response.headers().get("content-security-policy").unwrap().to_str().unwrap().to_owned();
+        let nonce = csp.split("'nonce-").nth(1).unwrap().split('\'').next().unwrap().to_owned();
+        assert!(csp.contains("frame-ancestors 'self'"));
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(std::str::from_utf8(&body).unwrap().contains(&format!(r#"<script nonce="{}">"#, nonce)));
+
+        for uri in ["/swagger-ui-bundle.js", "/swagger-ui-config.json", "/openapi.json"] {
+            let response = get(app.clone(), uri).await;
+            assert_eq!(response.headers().get("x-content-type-options").unwrap(), "nosniff");
+            assert_eq!(response.headers().get("referrer-policy").unwrap(), "no-referrer");
+            assert!(response.headers().get("content-security-policy").is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn does_cors() {
+        let mut options = Options::default();
+        options.cors = Some(swagger_ui::Cors {
+            allow_credentials: true,
+            ..swagger_ui::Cors::origins(["https://portal.example.com"])
+        });
+        let app = app_with_options(options);
+        let request = |method: Method, uri: &str, origin: &str| Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("origin", origin)
+            .header("access-control-request-headers", "authorization")
+            .body(Body::empty())
+            .unwrap();
+
+        for uri in ["/swagger-ui-config.json", "/openapi.json"] {
+            let response = app.clone().oneshot(request(Method::GET, uri, "https://portal.example.com")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers().get("access-control-allow-origin").unwrap(), "https://portal.example.com");
+            assert_eq!(response.headers().get("access-control-allow-credentials").unwrap(), "true");
+
+            let response = app.clone().oneshot(request(Method::OPTIONS, uri, "https://portal.example.com")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::NO_CONTENT);
+            assert_eq!(response.headers().get("access-control-allow-headers").unwrap(), "authorization");
+        }
+
+        let response = app.clone().oneshot(request(Method::GET, "/openapi.json", "https://evil.example.com")).await.unwrap();
+        assert!(response.headers().get("access-control-allow-origin").is_none());
+
+        let response = app.clone().oneshot(request(Method::GET, "/index.html", "https://portal.example.com")).await.unwrap();
+        assert!(response.headers().get("access-control-allow-origin").is_none());
+
+        let response = get(app_with_options(Options::default()), "/openapi.json").await;
+        assert!(response.headers().get("vary").is_none());
+    }
+
+    #[cfg(feature = "proxy")]
+    #[tokio::test]
+    async fn does_proxy() {
+        use std::io::{Read, Write};
+        use swagger_ui::proxy::Proxy;
+
+        // Stand-in upstream, answering one request with its request line
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let upstream = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![0; 4096];
+            let read = stream.read(&mut request).unwrap();
+            let line = String::from_utf8_lossy(&request[..read]).lines().next().unwrap_or_default().to_owned();
+            write!(stream, "HTTP/1.1 201 Created\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", line.len(), line).unwrap();
+        });
+        let mut options = Options::default();
+        options.proxy = Some(Proxy { body_limit: 4 * 1024 * 1024, ..Proxy::new([format!("{}/api", upstream)]) });
+        let app = app_with_options(options);
+        let proxied = |url: &str| format!("/proxy?url={}", url.replace(':', "%3A").replace('/', "%2F").replace('?', "%3F"));
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(proxied(&format!("{}/api/pets?limit=1", upstream)))
+            .body(Body::from("{}"))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers().get(CONTENT_TYPE).unwrap(), "text/plain");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body.as_ref(), b"POST /api/pets?limit=1 HTTP/1.1");
+
+        let response = get(app.clone(), &proxied(&format!("{}/admin", upstream))).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Bodies are limited by `Proxy::body_limit`, not the default limit of axum
+        for (size, status) in [(3 * 1024 * 1024, StatusCode::FORBIDDEN), (5 * 1024 * 1024, StatusCode::PAYLOAD_TOO_LARGE)] {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri(proxied(&format!("{}/admin", upstream)))
+                .body(Body::from(vec![b'x'; size]))
+                .unwrap();
+            assert_eq!(app.clone().oneshot(request).await.unwrap().status(), status);
+        }
+
+        let response = get(app, "/index.html").await;
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(std::str::from_utf8(&body).unwrap().contains("./proxy?url="));
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test]
+    async fn does_mock() {
+        let spec = swagger_ui::swagger_spec_file!("../../swagger-ui/examples/openapi.json");
+        let mut options = Options::default();
+        options.mock = Some(swagger_ui::mock::Mock::new(&spec).unwrap());
+        let app = Router::new().nest("/docs", app_with_options(options));
+
+        let response = get(app.clone(), "/docs/openapi.json").await;
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let spec: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(spec["basePath"], "/docs/mock");
+
+        let response = get(app.clone(), "/docs/mock/pet/42").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(CONTENT_TYPE).unwrap(), "application/json");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let pet: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(pet["photoUrls"], serde_json::json!(["string"]));
+
+        let response = get(app, "/docs/mock/owners").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[cfg(feature = "enforce")]
+    #[tokio::test]
+    async fn does_enforce() {
+        use axum::routing::{get as get_route, post};
+        use swagger_ui::enforce::{Enforcer, OnViolation};
+
+        let spec = swagger_ui::swagger_spec_file!("../../swagger-ui/examples/openapi.json");
+        let enforcer = Enforcer::new(&spec, OnViolation::Reject).unwrap().with_body_limit(64);
+        let app = Router::new()
+            .route("/v2/pet/:id", get_route(|| async { axum::Json(serde_json::json!({"name": "Rex"})) }))
+            .route("/v2/pet", post(|| async { StatusCode::METHOD_NOT_ALLOWED }))
+            .nest("/docs", swagger_ui_route(spec, None))
+            .layer(crate::EnforceLayer::new(enforcer));
+
+        let response = get(app.clone(), "/v2/pet/abc").await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["violations"], serde_json::json!(["path parameter `petId`: expected integer"]));
+
+        let response = get(app.clone(), "/v2/pet/1").await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let response = app.clone()
+            .oneshot(Request::builder().method(Method::POST).uri("/v2/pet")
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"name": "Rex", "photoUrls": []}"#))
+                .unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let post_pet = |body| app.clone()
+            .oneshot(Request::builder().method(Method::POST).uri("/v2/pet")
+                .header(CONTENT_TYPE, "application/json")
+                .body(body)
+                .unwrap());
+        let response = post_pet(Body::from("{}")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Bodies over the limit are rejected, bodies of unknown size are checked too
+        let response = post_pet(Body::from(format!(r#"{{"name": "{}"}}"#, "x".repeat(64)))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let streamed = |chunk: &'static str| {
+            let (mut sender, body) = Body::channel();
+            sender.try_send_data(chunk.into()).unwrap();
+            body
+        };
+        let response = post_pet(streamed("{}")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let response = post_pet(streamed(r#"{"name": "Rex", "photoUrls": []}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let response = get(app, "/docs/index.html").await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,148 @@
+use std::io::{Cursor, Read};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::handler::Outcome;
+use rocket::http::uri::Origin;
+use rocket::http::{ContentType, Method, Status};
+use rocket::{Data, Request, Response, Rocket, Route};
+use swagger_ui::enforce::{self, Enforcer, Location, OnViolation, Operation, Rejection};
+
+/// Path of the route answering rejected requests, mounted when the fairing is attached
+const REJECTED_PATH: &str = "/swagger-ui-enforce-rejected";
+
+/// How much of request bodies fairings can peek at
+const PEEK_SIZE: usize = 512;
+
+/// Fairing validating requests and responses of the operations documented in the spec,
+/// see `swagger_ui::enforce`. Requests to other routes pass through.
+///
+/// Rejected requests are rerouted to a route answering with the rejection, so their handler doesn't run.
+/// Fairings only see the first 512 bytes of request bodies, whatever `Enforcer::body_limit` is:
+/// larger ones are rejected with `OnViolation::Reject`, and pass through unchecked with `OnViolation::Log`.
+/// Responses are buffered up to `Enforcer::body_limit`.
+pub struct EnforceFairing {
+    enforcer: Enforcer,
+    /// Same as `enforcer`, limited to the request body data fairings can peek at
+    request_enforcer: Enforcer,
+}
+
+/// Outcome of the request checks, kept until the response
+#[derive(Default)]
+struct Checked(Option<(Operation, Result<(), Rejection>)>);
+
+impl EnforceFairing {
+    /// Create a new `EnforceFairing` validating with `enforcer`
+    pub fn new(enforcer: Enforcer) -> Self {
+        let request_limit = enforcer.body_limit().min(PEEK_SIZE);
+        Self { request_enforcer: enforcer.clone().with_body_limit(request_limit), enforcer }
+    }
+}
+
+impl Fairing for EnforceFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "OpenAPI enforcement",
+            kind: Kind::Attach | Kind::Request | Kind::Response,
+        }
+    }
+
+    fn on_attach(&self, rocket: Rocket) -> Result<Rocket, Rocket> {
+        let methods = [
+            Method::Get, Method::Put, Method::Post, Method::Delete, Method::Options,
+            Method::Head, Method::Trace, Method::Connect, Method::Patch,
+        ];
+        let routes = methods.iter()
+            .map(|method| Route::new(*method, REJECTED_PATH, rejected))
+            .collect::<Vec<_>>();
+        Ok(rocket.mount("/", routes))
+    }
+
+    fn on_request(&self, request: &mut Request, data: &Data) {
+        let operation = match self.enforcer.operation(request.method().as_str(), request.uri().path()) {
+            Some(operation) => operation,
+            None => return,
+        };
+        let enforcer = &self.request_enforcer;
+        let body = Some(data.peek()).filter(|body| data.peek_complete() && body.len() <= enforcer.body_limit());
+        let result = match body {
+            None if enforcer.on_violation() == OnViolation::Reject => Err(enforcer.body_too_large(Location::RequestBody)),
+            body => {
+                if body.is_none() {
+                    enforcer.skip_body(&operation, Location::RequestBody);
+                }
+                let headers = request.headers().iter()
+                    .map(|header| (header.name(), header.value()))
+                    .collect();
+                let checked = enforce::Request {
+                    query: request.uri().query(),
+                    headers,
+                    body,
+                };
+                enforcer.enforce_request(&operation, &checked)
+            }
+        };
+        let rejected = result.is_err();
+        request.local_cache(|| Checked(Some((operation, result))));
+        if rejected {
+            request.set_uri(Origin::parse(REJECTED_PATH).expect("valid rejection route path"));
+        }
+    }
+
+    fn on_response(&self, request: &Request, response: &mut Response) {
+        let operation = match &request.local_cache(Checked::default).0 {
+            Some((operation, Ok(()))) => operation,
+            // Rejected requests are answered by the rejection route
+            _ => return,
+        };
+
+        let mut body = vec![];
+        let mut rest = None;
+        if let Some(reader) = response.take_body().map(|body| body.into_inner()) {
+            let mut reader = reader.take(self.enforcer.body_limit() as u64 + 1);
+            if reader.read_to_end(&mut body).is_err() {
+                *response = Response::build().status(Status::InternalServerError).finalize();
+                return;
+            }
+            rest = Some(reader.into_inner());
+        }
+        if body.len() > self.enforcer.body_limit() {
+            match self.enforcer.on_violation() {
+                OnViolation::Reject => *response = rejection_response(&self.enforcer.body_too_large(Location::ResponseBody)),
+                OnViolation::Log => {
+                    self.enforcer.skip_body(operation, Location::ResponseBody);
+                    if let Some(rest) = rest {
+                        response.set_streamed_body(Cursor::new(body).chain(rest));
+                    }
+                }
+            }
+            return;
+        }
+
+        let content_type = response.headers().get_one("Content-Type").map(str::to_owned);
+        let checked = enforce::Response {
+            status: response.status().code,
+            content_type: content_type.as_deref(),
+            body: &body,
+        };
+        match self.enforcer.enforce_response(operation, &checked) {
+            Ok(()) if !body.is_empty() => response.set_sized_body(Cursor::new(body)),
+            Ok(()) => {}
+            Err(rejection) => *response = rejection_response(&rejection),
+        }
+    }
+}
+
+/// Handler of the rejection route, answering with the rejection of the request checks
+fn rejected<'r>(request: &'r Request, _: Data) -> Outcome<'r> {
+    match &request.local_cache(Checked::default).0 {
+        Some((_, Err(rejection))) => Outcome::from(request, rejection_response(rejection)),
+        _ => Outcome::failure(Status::NotFound),
+    }
+}
+
+fn rejection_response(rejection: &Rejection) -> Response<'static> {
+    Response::build()
+        .status(Status::from_code(rejection.status).unwrap_or(Status::InternalServerError))
+        .header(ContentType::JSON)
+        .sized_body(Cursor::new(rejection.body.to_vec()))
+        .finalize()
+}
@@ -1,13 +1,20 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::Arc;
 use rocket::handler::{Handler, Outcome};
-use rocket::http::{ContentType, Method};
-use rocket::response::{Content, Responder, Redirect};
+use rocket::http::{ContentType, Method, Status};
+use rocket::response::{Content, Responder, Redirect, Response};
 use rocket::{Data, Request, Route};
+use swagger_ui::{Bytes, Cors, FileResponse, Options, SecurityHeaders};
+use crate::mime_type;
 
 /// A content handler is a wrapper type around `rocket::response::Content`, which can be turned into
 /// a `rocket::Route` that serves the content with correct content-type.
 #[derive(Clone)]
 pub struct ContentHandler<R: Responder<'static> + Clone + Send + Sync + 'static> {
     content: Content<R>,
+    headers: Vec<(&'static str, String)>,
+    cors: Option<Cors>,
 }
 
 impl ContentHandler<String> {
@@ -17,6 +24,8 @@ impl ContentHandler<String> {
             serde_json::to_string_pretty(content).expect("Could not serialize content as JSON.");
         ContentHandler {
             content: Content(ContentType::JSON, json),
+            headers: vec![],
+            cors: None,
         }
     }
 }
@@ -27,24 +36,66 @@ impl ContentHandler<Vec<u8>> {
     pub fn bytes(content_type: ContentType, content: Vec<u8>) -> Self {
         ContentHandler {
             content: Content(content_type, content),
+            headers: vec![],
+            cors: None,
         }
     }
 }
 
 impl<R: Responder<'static> + Clone + Send + Sync + 'static> ContentHandler<R> {
+    /// Send the specified headers along with the content, e.g. `SecurityHeaders::headers`.
+    pub fn with_headers(mut self, headers: Vec<(&'static str, String)>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Send CORS headers along with the content, according to the specified policy.
+    pub fn with_cors(mut self, cors: Option<Cors>) -> Self {
+        self.cors = cors;
+        self
+    }
+
     /// Create a `rocket::Route` from the current `ContentHandler`.
     pub fn into_route(self, path: impl AsRef<str>) -> Route {
         Route::new(Method::Get, path, self)
     }
+
+    /// Create a `GET` route from the current `ContentHandler`,
+    /// along with an `OPTIONS` route answering CORS preflight requests if it has a CORS policy.
+    pub fn into_routes(self, path: impl AsRef<str>) -> Vec<Route> {
+        let mut routes = vec![];
+        if self.cors.is_some() {
+            routes.push(Route::new(Method::Options, path.as_ref(), self.clone()));
+        }
+        routes.push(self.into_route(path));
+        routes
+    }
 }
 
 impl<R: Responder<'static> + Clone + Send + Sync + 'static> Handler for ContentHandler<R> {
     fn handle<'r>(&self, req: &'r Request, data: Data) -> Outcome<'r> {
         // match e.g. "/index.html" but not "/index.html/"
         if req.uri().path().ends_with('/') {
-            Outcome::Forward(data)
-        } else {
-            Outcome::from(req, self.content.clone())
+            return Outcome::Forward(data);
+        }
+        let origin = req.headers().get_one("Origin");
+        if let (Some(cors), Method::Options) = (&self.cors, req.method()) {
+            let mut response = Response::build();
+            response.status(Status::NoContent);
+            for (name, value) in cors.preflight_headers(origin, req.headers().get_one("Access-Control-Request-Headers")) {
+                response.raw_header(name, value);
+            }
+            return Outcome::Success(response.finalize());
+        }
+        let cors_headers = self.cors.as_ref().map(|cors| cors.headers(origin)).unwrap_or_default();
+        match self.content.clone().respond_to(req) {
+            Ok(mut response) => {
+                for (name, value) in self.headers.iter().cloned().chain(cors_headers) {
+                    response.set_raw_header(name, value);
+                }
+                Outcome::Success(response)
+            }
+            Err(status) => Outcome::Failure(status),
         }
     }
 }
@@ -74,4 +125,281 @@ impl Handler for RedirectHandler {
         let path = req.route().unwrap().base().trim_end_matches('/');
         Outcome::from(req, Redirect::to(format!("{}/{}", path, self.dest)))
     }
-}
\ No newline at end of file
+}
+
+/// A handler that serves files looked up by path on each request, e.g. with `Options::file_response`.
+#[derive(Clone)]
+pub struct FileHandler {
+    lookup: Arc<dyn Fn(&str) -> Option<FileResponse> + Send + Sync>,
+}
+
+impl FileHandler {
+    /// Create a new `FileHandler` serving files returned by `lookup`.
+    pub fn new(lookup: impl Fn(&str) -> Option<Bytes> + Send + Sync + 'static) -> Self {
+        Self {
+            lookup: Arc::new(move |path| lookup(path).map(|content| FileResponse { content, headers: vec![] })),
+        }
+    }
+
+    /// Create a new `FileHandler` serving files returned by `lookup`, along with their headers.
+    pub fn responses(lookup: impl Fn(&str) -> Option<FileResponse> + Send + Sync + 'static) -> Self {
+        Self {
+            lookup: Arc::new(lookup),
+        }
+    }
+
+    /// Create a new `FileHandler` serving files of the specified `Options`, with their security headers.
+    pub fn options(options: Options) -> Self {
+        Self {
+            lookup: Arc::new(move |path| options.file_response(path)),
+        }
+    }
+
+    /// Create a new `Route` from this `Handler`, `path` must end with a `<path..>` segment.
+    pub fn into_route(self, path: impl AsRef<str>) -> Route {
+        Route::new(Method::Get, path, self)
+    }
+}
+
+impl Handler for FileHandler {
+    fn handle<'r>(&self, req: &'r Request, data: Data) -> Outcome<'r> {
+        let path = match req.get_segments::<PathBuf>(0) {
+            Some(Ok(path)) => path,
+            _ => return Outcome::Forward(data),
+        };
+        let path = path.to_string_lossy().replace('\\', "/");
+        let file = match (self.lookup)(&path) {
+            Some(file) => file,
+            None => return Outcome::Forward(data),
+        };
+        let mut response = Response::build();
+        response
+            .header(mime_type(&path))
+            .sized_body(Cursor::new(file.content.to_vec()));
+        for (name, value) in file.headers {
+            response.raw_header(name, value);
+        }
+        Outcome::Success(response.finalize())
+    }
+}
+
+/// A handler serving and, in dev mode, saving the spec edited with Swagger Editor.
+#[cfg(feature = "editor")]
+#[derive(Clone)]
+pub struct EditorSpecHandler {
+    editor: Arc<swagger_ui::editor::Editor>,
+    spec: Arc<swagger_ui::Spec>,
+}
+
+#[cfg(feature = "editor")]
+impl EditorSpecHandler {
+    /// Largest accepted spec, in bytes
+    const LIMIT: u64 = 16 * 1024 * 1024;
+
+    /// Create a new `EditorSpecHandler` for the specified `Editor` and `Spec`.
+    pub fn new(editor: Arc<swagger_ui::editor::Editor>, spec: swagger_ui::Spec) -> Self {
+        Self {
+            editor,
+            spec: Arc::new(spec),
+        }
+    }
+
+    /// Create `GET` and `PUT` routes from this `Handler`.
+    pub fn into_routes(self, path: impl AsRef<str>) -> Vec<Route> {
+        vec![
+            Route::new(Method::Get, path.as_ref(), self.clone()),
+            Route::new(Method::Put, path.as_ref(), self),
+        ]
+    }
+}
+
+#[cfg(feature = "editor")]
+impl Handler for EditorSpecHandler {
+    fn handle<'r>(&self, req: &'r Request, data: Data) -> Outcome<'r> {
+        use std::io::Read;
+        use rocket::response::status::Custom;
+        use swagger_ui::editor::SaveError;
+
+        if req.method() == Method::Get {
+            return match self.editor.spec_content(&self.spec) {
+                Ok(content) => {
+                    let mut response = Response::build();
+                    response
+                        .header(mime_type(&self.spec.name))
+                        .sized_body(Cursor::new(content.to_vec()));
+                    for (name, value) in SecurityHeaders::default().headers(&self.spec.name, None) {
+                        response.raw_header(name, value);
+                    }
+                    Outcome::Success(response.finalize())
+                }
+                Err(err) => Outcome::from(req, Custom(Status::InternalServerError, err.to_string())),
+            };
+        }
+
+        let mut body = vec![];
+        if let Err(err) = data.open().take(Self::LIMIT).read_to_end(&mut body) {
+            return Outcome::from(req, Custom(Status::BadRequest, err.to_string()));
+        }
+        match self.editor.save(&body) {
+            Ok(()) => Outcome::from(req, Status::NoContent),
+            Err(SaveError::ReadOnly) => Outcome::from(req, Status::MethodNotAllowed),
+            Err(err @ SaveError::Parse(_)) => Outcome::from(req, Custom(Status::BadRequest, err.to_string())),
+            Err(err @ SaveError::Io(_)) => Outcome::from(req, Custom(Status::InternalServerError, err.to_string())),
+        }
+    }
+}
+
+/// A handler forwarding "Try it out" requests to the allowed upstreams of a `Proxy`.
+#[cfg(feature = "proxy")]
+#[derive(Clone)]
+pub struct ProxyHandler {
+    proxy: Arc<swagger_ui::proxy::Proxy>,
+}
+
+#[cfg(feature = "proxy")]
+impl ProxyHandler {
+    /// Create a new `ProxyHandler` for the specified `Proxy`.
+    pub fn new(proxy: swagger_ui::proxy::Proxy) -> Self {
+        Self {
+            proxy: Arc::new(proxy),
+        }
+    }
+
+    /// Create routes for all methods from this `Handler`.
+    pub fn into_routes(self, path: impl AsRef<str>) -> Vec<Route> {
+        [Method::Get, Method::Head, Method::Post, Method::Put, Method::Patch, Method::Delete, Method::Options]
+            .iter()
+            .map(|method| Route::new(*method, path.as_ref(), self.clone()))
+            .collect()
+    }
+}
+
+#[cfg(feature = "proxy")]
+impl Handler for ProxyHandler {
+    fn handle<'r>(&self, req: &'r Request, data: Data) -> Outcome<'r> {
+        use std::io::Read;
+        use rocket::response::status::Custom;
+        use swagger_ui::proxy::{ProxyError, ProxyRequest};
+
+        let mut body = vec![];
+        if let Err(err) = data.open().take(self.proxy.body_limit + 1).read_to_end(&mut body) {
+            return Outcome::from(req, Custom(Status::BadRequest, err.to_string()));
+        }
+        let request = ProxyRequest {
+            method: req.method().as_str().to_owned(),
+            query: req.uri().query().map(str::to_owned),
+            headers: req.headers().iter().map(|h| (h.name().to_owned(), h.value().to_owned())).collect(),
+            body: Bytes::from(body),
+        };
+        let status = |code: u16| Status::from_code(code).unwrap_or(Status::new(code, "Unknown"));
+        match self.proxy.forward(request) {
+            Ok(upstream) => {
+                let mut response = Response::build();
+                response
+                    .status(status(upstream.status))
+                    .sized_body(Cursor::new(upstream.body.to_vec()));
+                for (name, value) in upstream.headers {
+                    response.raw_header_adjoin(name, value);
+                }
+                Outcome::Success(response.finalize())
+            }
+            Err(err @ ProxyError::TooLarge) => Outcome::from(req, Custom(Status::PayloadTooLarge, err.to_string())),
+            Err(err) => Outcome::from(req, Custom(status(err.status()), err.to_string())),
+        }
+    }
+}
+
+/// A handler answering requests to the documented operations of a spec with mock responses.
+#[cfg(feature = "mock")]
+#[derive(Clone)]
+pub struct MockHandler {
+    mock: Arc<swagger_ui::mock::Mock>,
+}
+
+#[cfg(feature = "mock")]
+impl MockHandler {
+    /// Create a new `MockHandler` for the specified `Mock`.
+    pub fn new(mock: Arc<swagger_ui::mock::Mock>) -> Self {
+        Self { mock }
+    }
+
+    /// Create routes for all methods from this `Handler`, `path` must end with a `<path..>` segment.
+    pub fn into_routes(self, path: impl AsRef<str>) -> Vec<Route> {
+        [Method::Get, Method::Head, Method::Post, Method::Put, Method::Patch, Method::Delete, Method::Options]
+            .iter()
+            .map(|method| Route::new(*method, path.as_ref(), self.clone()))
+            .collect()
+    }
+}
+
+#[cfg(feature = "mock")]
+impl Handler for MockHandler {
+    fn handle<'r>(&self, req: &'r Request, data: Data) -> Outcome<'r> {
+        use rocket::response::status::Custom;
+
+        let path = match req.get_segments::<PathBuf>(0) {
+            Some(Ok(path)) => path,
+            _ => return Outcome::Forward(data),
+        };
+        let path = path.to_string_lossy().replace('\\', "/");
+        match self.mock.respond(req.method().as_str(), &path) {
+            Some(mocked) => {
+                let mut response = Response::build();
+                response
+                    .status(Status::from_code(mocked.status).unwrap_or(Status::Ok))
+                    .sized_body(Cursor::new(mocked.body.to_vec()));
+                if let Some(content_type) = mocked.content_type {
+                    response.raw_header("Content-Type", content_type);
+                }
+                Outcome::Success(response.finalize())
+            }
+            None => Outcome::from(req, Custom(Status::NotFound, "no documented operation")),
+        }
+    }
+}
+
+/// A handler serving the spec of a `Mock`, pointing "Try it out" to the mock routes.
+#[cfg(feature = "mock")]
+#[derive(Clone)]
+pub struct MockSpecHandler {
+    mock: Arc<swagger_ui::mock::Mock>,
+    spec_name: String,
+    headers: Vec<(&'static str, String)>,
+    cors: Option<Cors>,
+}
+
+#[cfg(feature = "mock")]
+impl MockSpecHandler {
+    /// Create a new `MockSpecHandler` serving the spec named `spec_name`, with the specified headers and CORS policy.
+    pub fn new(mock: Arc<swagger_ui::mock::Mock>, spec_name: &str, headers: Vec<(&'static str, String)>, cors: Option<Cors>) -> Self {
+        Self {
+            mock,
+            spec_name: spec_name.to_owned(),
+            headers,
+            cors,
+        }
+    }
+
+    /// Create `GET` and, with a CORS policy, `OPTIONS` routes from this `Handler`.
+    pub fn into_routes(self, path: impl AsRef<str>) -> Vec<Route> {
+        let mut routes = vec![];
+        if self.cors.is_some() {
+            routes.push(Route::new(Method::Options, path.as_ref(), self.clone()));
+        }
+        routes.push(Route::new(Method::Get, path, self));
+        routes
+    }
+}
+
+#[cfg(feature = "mock")]
+impl Handler for MockSpecHandler {
+    fn handle<'r>(&self, req: &'r Request, data: Data) -> Outcome<'r> {
+        let path = req.uri().path();
+        let base = path.strip_suffix(self.spec_name.as_str()).unwrap_or("/");
+        let content = self.mock.spec_content(&format!("{}{}", base, swagger_ui::mock::MOCK_PATH));
+        ContentHandler::bytes(ContentType::JSON, content.to_vec())
+            .with_headers(self.headers.clone())
+            .with_cors(self.cors.clone())
+            .handle(req, data)
+    }
+}
@@ -1,12 +1,18 @@
 mod handlers;
+#[cfg(feature = "enforce")]
+mod enforce;
+
+#[cfg(feature = "enforce")]
+pub use enforce::EnforceFairing;
 
 use rocket::http::{ContentType};
 use rocket::{Route};
-use crate::handlers::{ContentHandler, RedirectHandler};
-use swagger_ui::{Assets, Config, Spec};
+use crate::handlers::{ContentHandler, FileHandler, RedirectHandler};
+use swagger_ui::{Config, Options, SecurityHeaders, Spec, SpecOrUrl, Versions};
+use swagger_ui::renderer::Renderer;
 use std::path::Path;
 
-fn mime_type(filename: &str) -> ContentType {
+pub(crate) fn mime_type(filename: &str) -> ContentType {
     let parts = filename.split('.').collect::<Vec<&str>>();
     match parts.last() {
         Some(v) =>
@@ -15,17 +21,23 @@ fn mime_type(filename: &str) -> ContentType {
                 "js" => ContentType::JavaScript,
                 "png" => ContentType::PNG,
                 "css" => ContentType::CSS,
+                "svg" => ContentType::SVG,
                 _ => ContentType::Plain
             },
         _ => ContentType::Plain
     }
 }
 
-pub fn routes(spec: Spec, mut config: Config) -> Vec<Route> {
+pub fn routes(spec: Spec, config: Config) -> Vec<Route> {
+    routes_with_options(spec, config, Options::default())
+}
+
+/// Same as `routes`, with additional server-side options
+pub fn routes_with_options(spec: Spec, mut config: Config, options: Options) -> Vec<Route> {
     let spec_handler =
         ContentHandler::bytes(
             mime_type(spec.name.as_ref()),
-            spec.content.into(),
+            spec.content.to_vec(),
         );
 
     let spec_name: &str =
@@ -37,27 +49,109 @@ pub fn routes(spec: Spec, mut config: Config) -> Vec<Route> {
 
     config.url = String::from(spec_name);
 
-    let config_handler = ContentHandler::json(&config);
+    let config_handler = ContentHandler::json(&config)
+        .with_headers(options.security_headers.headers("swagger-ui-config.json", None))
+        .with_cors(options.cors.clone());
+    let spec_handler = spec_handler
+        .with_headers(options.security_headers.headers(spec_name, None))
+        .with_cors(options.cors.clone());
 
-    let mut routes = vec![
-        config_handler.into_route(format!("/{}", "swagger-ui-config.json")),
-        spec_handler.into_route(format!("/{}", spec_name)),
-        RedirectHandler::to("index.html").into_route("/"),
-    ];
+    let mut routes = config_handler.into_routes(format!("/{}", "swagger-ui-config.json"));
+    let spec_routes = spec_handler.into_routes(format!("/{}", spec_name));
+    // In mock mode the mocked spec replaces the spec, pointing "Try it out" to the mock routes
+    #[cfg(feature = "mock")]
+    let spec_routes = if let Some(mock) = options.mock.clone() {
+        let mock = std::sync::Arc::new(mock);
+        let headers = options.security_headers.headers(spec_name, None);
+        routes.extend(handlers::MockHandler::new(mock.clone()).into_routes(format!("/{}/<path..>", swagger_ui::mock::MOCK_PATH)));
+        handlers::MockSpecHandler::new(mock, spec_name, headers, options.cors.clone())
+            .into_routes(format!("/{}", spec_name))
+    } else {
+        spec_routes
+    };
+    routes.extend(spec_routes);
+    routes.push(RedirectHandler::to("index.html").into_route("/"));
+    #[cfg(feature = "proxy")]
+    if let Some(proxy) = options.proxy.clone() {
+        routes.extend(handlers::ProxyHandler::new(proxy).into_routes(format!("/{}", swagger_ui::proxy::PROXY_PATH)));
+    }
+    routes.push(FileHandler::options(options).into_route("/<path..>"));
+    routes
+}
 
-    for file in Assets::iter() {
-        let filename = file.as_ref();
-        let mime_type = mime_type(filename);
+/// Returns routes serving the swagger-ui page displaying several versions of a spec, each at
+/// `{version}/{spec name}`, with a version selector, see `swagger_ui::versions`
+pub fn versioned_routes(versions: Versions, config: Config) -> Vec<Route> {
+    versioned_routes_with_options(versions, config, Options::default())
+}
 
-        let content: Vec<u8> = Assets::get(filename).unwrap().into_owned();
+/// Same as `versioned_routes`, with additional server-side options. Mock mode is not available for versions.
+pub fn versioned_routes_with_options(versions: Versions, config: Config, options: Options) -> Vec<Route> {
+    // Spec urls are relative to the index page, like in `routes`
+    let config = versions.config(&config, "");
+    let config_handler = ContentHandler::json(&config)
+        .with_headers(options.security_headers.headers("swagger-ui-config.json", None))
+        .with_cors(options.cors.clone());
 
-        let path = format!("/{}", filename);
-        let handler = ContentHandler::bytes(mime_type, content);
-        let route = handler.into_route(path);
+    let mut routes = config_handler.into_routes(format!("/{}", "swagger-ui-config.json"));
+    for (version, spec) in versions.specs() {
+        let spec_path = Versions::spec_path(version, spec);
+        let spec_handler = ContentHandler::bytes(mime_type(spec.name.as_ref()), spec.content.to_vec())
+            .with_headers(options.security_headers.headers(&spec_path, None))
+            .with_cors(options.cors.clone());
+        routes.extend(spec_handler.into_routes(format!("/{}", spec_path)));
+    }
+    routes.push(RedirectHandler::to("index.html").into_route("/"));
+    #[cfg(feature = "proxy")]
+    if let Some(proxy) = options.proxy.clone() {
+        routes.extend(handlers::ProxyHandler::new(proxy).into_routes(format!("/{}", swagger_ui::proxy::PROXY_PATH)));
+    }
+    routes.push(FileHandler::options(options).into_route("/<path..>"));
+    routes
+}
 
-        routes.push(route);
+/// Returns routes rendering the spec with another `Renderer`, to be mounted next to the swagger-ui ones.
+/// Mount them several times to render the same spec with several renderers.
+pub fn renderer_routes(renderer: impl Renderer, spec: impl Into<SpecOrUrl>) -> Vec<Route> {
+    let mut routes = vec![];
+    let spec_url = match spec.into() {
+        SpecOrUrl::Spec(spec) => {
+            let spec_name = spec.name.trim_start_matches('/').to_owned();
+            let spec_handler =
+                ContentHandler::bytes(
+                    mime_type(&spec_name),
+                    spec.content.into(),
+                )
+                .with_headers(SecurityHeaders::default().headers(&spec_name, None));
+            routes.push(spec_handler.into_route(format!("/{}", spec_name)));
+            format!("./{}", spec_name)
+        }
+        SpecOrUrl::Url(url) => url.into_owned(),
     };
 
+    routes.push(RedirectHandler::to("index.html").into_route("/"));
+    routes.push(FileHandler::responses(move |path| renderer.file_response(path, &spec_url)).into_route("/<path..>"));
+    routes
+}
+
+/// Returns routes rendering the spec with Redoc, to be mounted next to the swagger-ui ones
+#[cfg(feature = "redoc")]
+pub fn redoc_routes(spec: impl Into<SpecOrUrl>) -> Vec<Route> {
+    renderer_routes(swagger_ui::renderer::Redoc, spec)
+}
+
+/// Returns routes serving Swagger Editor preloaded with the spec.
+/// In dev mode (`Editor::saving_to`) the spec is read from and saved to its source file with `GET`/`PUT` requests.
+#[cfg(feature = "editor")]
+pub fn editor_routes(editor: swagger_ui::editor::Editor, spec: Spec) -> Vec<Route> {
+    let spec_name = spec.name.trim_start_matches('/').to_owned();
+    let spec_url = format!("./{}", spec_name);
+    let editor = std::sync::Arc::new(editor);
+
+    let mut routes = handlers::EditorSpecHandler::new(editor.clone(), spec)
+        .into_routes(format!("/{}", spec_name));
+    routes.push(RedirectHandler::to("index.html").into_route("/"));
+    routes.push(FileHandler::responses(move |path| editor.file_response(path, &spec_url)).into_route("/<path..>"));
     routes
 }
 
@@ -79,6 +173,218 @@ mod tests {
             )
     }
 
+    #[test]
+    fn swagger_ui_overlay() {
+        let mut options = swagger_ui::Options::default();
+        options.overlay = swagger_ui::Overlay::new().file("logo.svg", swagger_ui::Bytes::from_static(b"<svg/>"));
+        let rocket = rocket::ignite()
+            .mount("/api/v1/swagger/",
+                   super::routes_with_options(
+                       swagger_ui::swagger_spec_file!("../../swagger-ui/examples/openapi.json"),
+                       swagger_ui::Config { ..Default::default() },
+                       options,
+                   ),
+            );
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/api/v1/swagger/logo.svg").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string().unwrap(), "<svg/>");
+
+        let response = client.get("/api/v1/swagger/missing.js").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn security_headers() {
+        let client = Client::new(ignite()).expect("valid rocket instance");
+
+        let mut response = client.get("/api/v1/swagger/index.html").dispatch();
+        let csp = response.headers().get_one("Content-Security-Policy").unwrap().to_owned();
+        let nonce = csp.split("'nonce-").nth(1).unwrap().split('\'').next().unwrap().to_owned();
+        assert!(response.body_string().unwrap().contains(&format!(r#"<script nonce="{}">"#, nonce)));
+
+        for uri in ["/api/v1/swagger/swagger-ui-bundle.js", "/api/v1/swagger/swagger-ui-config.json", "/api/v1/swagger/openapi.json"] {
+            let response = client.get(uri).dispatch();
+            assert_eq!(response.headers().get_one("X-Content-Type-Options"), Some("nosniff"));
+            assert_eq!(response.headers().get_one("Content-Security-Policy"), None);
+        }
+    }
+
+    #[test]
+    fn cors() {
+        use rocket::http::Header;
+
+        let mut options = swagger_ui::Options::default();
+        options.cors = Some(swagger_ui::Cors::origins(["https://portal.example.com"]));
+        let rocket = rocket::ignite()
+            .mount("/api/v1/swagger/",
+                   super::routes_with_options(
+                       swagger_ui::swagger_spec_file!("../../swagger-ui/examples/openapi.json"),
+                       swagger_ui::Config { ..Default::default() },
+                       options,
+                   ),
+            );
+        let client = Client::new(rocket).expect("valid rocket instance");
+        let origin = || Header::new("Origin", "https://portal.example.com");
+
+        for uri in ["/api/v1/swagger/swagger-ui-config.json", "/api/v1/swagger/openapi.json"] {
+            let response = client.get(uri).header(origin()).dispatch();
+            assert_eq!(response.status(), Status::Ok);
+            assert_eq!(response.headers().get_one("Access-Control-Allow-Origin"), Some("https://portal.example.com"));
+
+            let response = client.options(uri).header(origin()).dispatch();
+            assert_eq!(response.status(), Status::NoContent);
+            assert_eq!(response.headers().get_one("Access-Control-Allow-Methods"), Some("GET, HEAD, OPTIONS"));
+        }
+
+        let response = client.get("/api/v1/swagger/openapi.json")
+            .header(Header::new("Origin", "https://evil.example.com"))
+            .dispatch();
+        assert_eq!(response.headers().get_one("Access-Control-Allow-Origin"), None);
+    }
+
+    #[test]
+    fn versions() {
+        let spec = swagger_ui::swagger_spec_file!("../../swagger-ui/examples/openapi.json");
+        let versions = swagger_ui::Versions::new()
+            .with_version("v1", spec.clone())
+            .with_version("v2", spec);
+        let rocket = rocket::ignite()
+            .mount("/api/swagger/", super::versioned_routes(versions, swagger_ui::Config { ..Default::default() }));
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/api/swagger/swagger-ui-config.json").dispatch();
+        let config: serde_json::Value = serde_json::from_str(&response.body_string().unwrap()).unwrap();
+        assert_eq!(config["urls"][0]["url"], "v2/openapi.json");
+        assert_eq!(config["urls.primaryName"], "v2");
+
+        let response = client.get("/api/swagger/v1/openapi.json").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[cfg(feature = "proxy")]
+    #[test]
+    fn proxy() {
+        use std::io::{Read, Write};
+        use swagger_ui::proxy::Proxy;
+
+        // Stand-in upstream, answering one request with its request line
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let upstream = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![0; 4096];
+            let read = stream.read(&mut request).unwrap();
+            let line = String::from_utf8_lossy(&request[..read]).lines().next().unwrap_or_default().to_owned();
+            write!(stream, "HTTP/1.1 201 Created\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", line.len(), line).unwrap();
+        });
+        let mut options = swagger_ui::Options::default();
+        options.proxy = Some(Proxy::new([format!("{}/api", upstream)]));
+        let rocket = rocket::ignite()
+            .mount("/api/v1/swagger/",
+                   super::routes_with_options(
+                       swagger_ui::swagger_spec_file!("../../swagger-ui/examples/openapi.json"),
+                       swagger_ui::Config { ..Default::default() },
+                       options,
+                   ),
+            );
+        let client = Client::new(rocket).expect("valid rocket instance");
+        let proxied = |url: &str| format!("/api/v1/swagger/proxy?url={}", url.replace(':', "%3A").replace('/', "%2F").replace('?', "%3F"));
+
+        let mut response = client.post(proxied(&format!("{}/api/pets?limit=1", upstream))).body("{}").dispatch();
+        assert_eq!(response.status(), Status::Created);
+        assert_eq!(response.body_string().unwrap(), "POST /api/pets?limit=1 HTTP/1.1");
+
+        let response = client.get(proxied(&format!("{}/admin", upstream))).dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn mock() {
+        let spec = swagger_ui::swagger_spec_file!("../../swagger-ui/examples/openapi.json");
+        let mut options = swagger_ui::Options::default();
+        options.mock = Some(swagger_ui::mock::Mock::new(&spec).unwrap());
+        let rocket = rocket::ignite()
+            .mount("/api/v1/swagger/",
+                   super::routes_with_options(
+                       spec,
+                       swagger_ui::Config { ..Default::default() },
+                       options,
+                   ),
+            );
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/api/v1/swagger/openapi.json").dispatch();
+        let spec: serde_json::Value = serde_json::from_str(&response.body_string().unwrap()).unwrap();
+        assert_eq!(spec["basePath"], "/api/v1/swagger/mock");
+
+        let mut response = client.get("/api/v1/swagger/mock/pet/findByStatus?status=available").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let pets: serde_json::Value = serde_json::from_str(&response.body_string().unwrap()).unwrap();
+        assert_eq!(pets[0]["photoUrls"], serde_json::json!(["string"]));
+
+        let response = client.get("/api/v1/swagger/mock/unknown").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[cfg(feature = "enforce")]
+    #[test]
+    fn enforce() {
+        use rocket::handler::Outcome;
+        use rocket::http::{ContentType, Method};
+        use rocket::response::Content;
+        use rocket::{Data, Request, Route};
+        use swagger_ui::enforce::{Enforcer, OnViolation};
+
+        fn pet<'r>(req: &'r Request, _: Data) -> Outcome<'r> {
+            Outcome::from(req, Content(ContentType::JSON, r#"{"name": "Rex"}"#))
+        }
+        static ADDED: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        fn add_pet<'r>(req: &'r Request, _: Data) -> Outcome<'r> {
+            ADDED.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Outcome::from(req, Status::MethodNotAllowed)
+        }
+
+        let spec = swagger_ui::swagger_spec_file!("../../swagger-ui/examples/openapi.json");
+        let enforcer = Enforcer::new(&spec, OnViolation::Reject).unwrap();
+        let rocket = rocket::ignite()
+            .mount("/v2", vec![Route::new(Method::Get, "/pet/<id>", pet), Route::new(Method::Post, "/pet", add_pet)])
+            .mount("/docs", super::routes(spec, swagger_ui::Config { ..Default::default() }))
+            .attach(super::EnforceFairing::new(enforcer));
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/v2/pet/abc").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let body: serde_json::Value = serde_json::from_str(&response.body_string().unwrap()).unwrap();
+        assert_eq!(body["violations"], serde_json::json!(["path parameter `petId`: expected integer"]));
+
+        let response = client.get("/v2/pet/1").dispatch();
+        assert_eq!(response.status(), Status::InternalServerError);
+
+        let response = client.post("/v2/pet")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Rex", "photoUrls": []}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::MethodNotAllowed);
+        assert_eq!(ADDED.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // Rejected requests don't reach their handler
+        let response = client.post("/v2/pet").header(ContentType::JSON).body("{}").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        assert_eq!(ADDED.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // Bodies larger than the fairings can peek at are rejected
+        let body = format!(r#"{{"name": "{}", "photoUrls": []}}"#, "x".repeat(600));
+        let response = client.post("/v2/pet").header(ContentType::JSON).body(body).dispatch();
+        assert_eq!(response.status(), Status::PayloadTooLarge);
+        assert_eq!(ADDED.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        let response = client.get("/docs/index.html").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
     #[test]
     fn swagger_ui() {
         let client = Client::new(ignite()).expect("valid rocket instance");
@@ -104,4 +410,65 @@ mod tests {
             String::from_utf8(std::fs::read(path).unwrap()).unwrap()
         );
     }
-}
+
+    #[cfg(feature = "redoc")]
+    #[test]
+    fn redoc() {
+        let rocket = rocket::ignite()
+            .mount("/api/v1/redoc/",
+                   super::redoc_routes(swagger_ui::swagger_spec_file!("../../swagger-ui/examples/openapi.json")),
+            );
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.get("/api/v1/redoc").dispatch();
+        assert_eq!(response.status(), Status::SeeOther);
+
+        let mut response = client.get("/api/v1/redoc/index.html").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(response.body_string().unwrap().contains(r#"spec-url="./openapi.json""#));
+
+        let response = client.get("/api/v1/redoc/redoc.standalone.js").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.get("/api/v1/redoc/openapi.json").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[cfg(feature = "redoc")]
+    #[test]
+    fn redoc_remote_spec() {
+        let rocket = rocket::ignite()
+            .mount("/api/v1/redoc/", super::redoc_routes("https://example.com/openapi.json"));
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/api/v1/redoc/index.html").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(response.body_string().unwrap().contains(r#"spec-url="https://example.com/openapi.json""#));
+
+        let response = client.get("/api/v1/redoc/openapi.json").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[cfg(feature = "editor")]
+    #[test]
+    fn editor() {
+        let source = std::env::temp_dir().join(format!("rocket-swagger-ui-editor-{}.yaml", std::process::id()));
+        std::fs::write(&source, "openapi: 3.0.0\n").unwrap();
+        let spec = swagger_ui::Spec { name: "openapi.yaml".into(), content: swagger_ui::Bytes::from_static(b"openapi: 3.0.0\n") };
+        let rocket = rocket::ignite()
+            .mount("/editor/", super::editor_routes(swagger_ui::editor::Editor::saving_to(&source), spec));
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.get("/editor/index.html").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.put("/editor/openapi.yaml").body("openapi: [").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let response = client.put("/editor/openapi.yaml").body("openapi: 3.0.3\n").dispatch();
+        assert_eq!(response.status(), Status::NoContent);
+
+        let mut response = client.get("/editor/openapi.yaml").dispatch();
+        assert_eq!(response.body_string().unwrap(), "openapi: 3.0.3\n");
+        std::fs::remove_file(source).unwrap();
+    }
+}
\ No newline at end of file
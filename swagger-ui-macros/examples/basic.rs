@@ -0,0 +1,9 @@
+use swagger_ui::Spec;
+use swagger_ui_macros::swagger_spec_file_checked;
+
+fn main() {
+    // Load openapi spec (compile-time), path is relative to the crate root.
+    // Fails to compile if the spec is invalid.
+    let spec: Spec = swagger_spec_file_checked!("../swagger-ui/examples/openapi.json");
+    println!("{}: {} bytes", spec.name, spec.content.len());
+}
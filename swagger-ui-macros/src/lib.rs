@@ -0,0 +1,126 @@
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, LitStr};
+use swagger_ui::validate::{validate_value, ValidationErrorKind};
+use swagger_ui::{Bytes, Spec};
+
+/// Same as `swagger_ui::swagger_spec_file!`, but checks the spec at compile time.
+///
+/// The path is relative to the crate root (`CARGO_MANIFEST_DIR`).
+/// Invalid JSON/YAML, documents which are not OpenAPI 2.0/3.0/3.1 shaped
+/// and dangling local `$ref`s are reported as compile errors, with line and column when known.
+/// Dangling `$ref`s are reported with the JSON pointer of the reference.
+#[proc_macro]
+pub fn swagger_spec_file_checked(input: TokenStream) -> TokenStream {
+    let lit = parse_macro_input!(input as LitStr);
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let path = PathBuf::from(manifest_dir).join(lit.value());
+
+    let content = match std::fs::read(&path) {
+        Ok(content) => content,
+        Err(err) => {
+            return syn::Error::new(lit.span(), format!("failed to read {}: {}", path.display(), err))
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let errors = check(&path, content);
+    if !errors.is_empty() {
+        let errors = errors.into_iter().map(|e| syn::Error::new(lit.span(), e).to_compile_error());
+        return quote!(#(#errors)*).into();
+    }
+
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("openapi.json");
+    let path = LitStr::new(&path.to_string_lossy(), Span::call_site());
+    quote! {
+        ::swagger_ui::Spec {
+            name: ::std::borrow::Cow::Borrowed(#name),
+            content: ::swagger_ui::Bytes::from_static(include_bytes!(#path))
+        }
+    }.into()
+}
+
+/// Parses and validates spec content, returns error messages
+fn check(path: &Path, content: Vec<u8>) -> Vec<String> {
+    let spec = Spec {
+        name: Cow::Owned(path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()),
+        content: Bytes::from(content),
+    };
+    let display = path.display();
+
+    let document = match spec.parse() {
+        Ok(document) => document,
+        Err(err) => {
+            return vec![match (err.line, err.column) {
+                (Some(line), Some(column)) => format!("{}:{}:{}: invalid spec: {}", display, line, column, err.message),
+                _ => format!("{}: invalid spec: {}", display, err.message),
+            }];
+        }
+    };
+
+    match validate_value(&document) {
+        Ok(_) => vec![],
+        Err(errors) => errors.into_iter().map(|error| {
+            match &error.kind {
+                ValidationErrorKind::UnresolvedRef(reference) => match unique_position(&spec.content, reference) {
+                    Some((line, column)) => format!("{}:{}:{}: {}", display, line, column, error),
+                    None => format!("{}: {}", display, error),
+                },
+                _ => format!("{}: {}", display, error),
+            }
+        }).collect(),
+    }
+}
+
+/// Finds line and column (1-based) of `needle` in `content`, if it occurs only once,
+/// otherwise the occurrence can't be told apart from the others
+fn unique_position(content: &[u8], needle: &str) -> Option<(usize, usize)> {
+    let content = std::str::from_utf8(content).ok()?;
+    let mut offsets = content.match_indices(needle).map(|(offset, _)| offset);
+    let offset = offsets.next().filter(|_| offsets.next().is_none())?;
+    let before = &content[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map(|l| l.chars().count()).unwrap_or(0) + 1;
+    Some((line, column))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use super::*;
+
+    #[test]
+    fn reports_parse_error_position() {
+        let errors = check(Path::new("openapi.json"), b"{\n  \"swagger\": \"2.0\",\n  ]\n}".to_vec());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("openapi.json:3:3: invalid spec"), "{}", errors[0]);
+    }
+
+    #[test]
+    fn reports_dangling_ref_position() {
+        let spec = "openapi: 3.0.3\ninfo:\n  title: api\n  version: '1'\npaths:\n  /pets:\n    get:\n      responses:\n        200:\n          $ref: '#/components/responses/Pets'\n";
+        let errors = check(Path::new("openapi.yaml"), spec.as_bytes().to_vec());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("openapi.yaml:10:18: "), "{}", errors[0]);
+    }
+
+    #[test]
+    fn reports_repeated_dangling_refs_by_pointer() {
+        let spec = "openapi: 3.0.3\ninfo:\n  title: api\n  version: '1'\npaths:\n  /pets:\n    get:\n      responses:\n        200:\n          $ref: '#/components/responses/Pets'\n    post:\n      responses:\n        200:\n          $ref: '#/components/responses/Pets'\n";
+        let errors = check(Path::new("openapi.yaml"), spec.as_bytes().to_vec());
+        assert_eq!(errors, [
+            r##"openapi.yaml: /paths/~1pets/get/responses/200/$ref: unresolved reference "#/components/responses/Pets""##,
+            r##"openapi.yaml: /paths/~1pets/post/responses/200/$ref: unresolved reference "#/components/responses/Pets""##,
+        ]);
+    }
+
+    #[test]
+    fn accepts_example_spec() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../swagger-ui/examples/openapi.json");
+        assert!(check(&path, std::fs::read(&path).unwrap()).is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use anyhow::Context;
 use async_recursion::async_recursion;
 use serde::Deserialize;
@@ -37,10 +37,71 @@ impl<T> From<OneOrMany<T>> for Vec<T> {
     }
 }
 
+/// Files of optional features: cargo feature, url, destination relative to the crate root
+const OPTIONAL_FILES: &[(&str, &str, &str)] = &[
+    ("REDOC", "https://cdn.jsdelivr.net/npm/redoc@2.1.3/bundles/redoc.standalone.js", ".redoc/redoc.standalone.js"),
+    ("RAPIDOC", "https://cdn.jsdelivr.net/npm/rapidoc@9.3.4/dist/rapidoc-min.js", ".rapidoc/rapidoc-min.js"),
+    ("SCALAR", "https://cdn.jsdelivr.net/npm/@scalar/api-reference@1.25.11/dist/browser/standalone.js", ".scalar/scalar.standalone.js"),
+    ("EDITOR", "https://cdn.jsdelivr.net/npm/swagger-editor-dist@4.13.1/swagger-editor-bundle.js", ".editor/swagger-editor-bundle.js"),
+    ("EDITOR", "https://cdn.jsdelivr.net/npm/swagger-editor-dist@4.13.1/swagger-editor-standalone-preset.js", ".editor/swagger-editor-standalone-preset.js"),
+    ("EDITOR", "https://cdn.jsdelivr.net/npm/swagger-editor-dist@4.13.1/swagger-editor.css", ".editor/swagger-editor.css"),
+];
+
+/// swagger-ui-dist files embedded in `Assets` unless `all-assets` feature is enabled
+const ASSETS: &[&str] = &[
+    "favicon-16x16.png",
+    "favicon-32x32.png",
+    "oauth2-redirect.html",
+    "oauth2-redirect.js",
+    "swagger-ui-bundle.js",
+    "swagger-ui.css",
+];
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let out_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR")?).join(".dist");
-    download_folder("https://api.github.com/repos/swagger-api/swagger-ui/contents/dist", out_dir).await?;
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR")?);
+    let dist = manifest_dir.join(".dist");
+    download_folder("https://api.github.com/repos/swagger-api/swagger-ui/contents/dist", dist.clone()).await?;
+    for (feature, url, to) in OPTIONAL_FILES {
+        if feature_enabled(feature) {
+            download_file(*url, manifest_dir.join(to)).await
+                .with_context(||format!("failed to download {url}"))?;
+        }
+    }
+    select_assets(&dist, &PathBuf::from(std::env::var("OUT_DIR")?).join("dist"))
+        .context("failed to select assets")
+}
+
+fn feature_enabled(feature: &str) -> bool {
+    std::env::var_os(format!("CARGO_FEATURE_{feature}")).is_some()
+}
+
+/// Copies the dist files selected by cargo features into `to`, which is embedded in `Assets`
+fn select_assets(dist: &Path, to: &Path) -> anyhow::Result<()> {
+    let mut files: Vec<String> = ASSETS.iter().map(|file| file.to_string()).collect();
+    if feature_enabled("STANDALONE_PRESET") {
+        files.push("swagger-ui-standalone-preset.js".to_owned());
+    }
+    if feature_enabled("SOURCE_MAPS") {
+        let maps: Vec<String> = files.iter().map(|file| format!("{file}.map")).collect();
+        files.extend(maps);
+    }
+    if feature_enabled("ALL_ASSETS") {
+        files = std::fs::read_dir(dist)?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+            .collect::<anyhow::Result<_>>()?;
+    }
+
+    if to.exists() {
+        std::fs::remove_dir_all(to)?;
+    }
+    std::fs::create_dir_all(to)?;
+    for file in files {
+        let from = dist.join(&file);
+        if from.is_file() {
+            std::fs::copy(from, to.join(file))?;
+        }
+    }
     Ok(())
 }
 
@@ -16,6 +16,7 @@ fn main() {
     let _config: Config = Config {
         url: "".to_string(),
         urls: vec![],
+        urls_primary_name: "".to_string(),
         deep_linking: false,
         display_operation_id: false,
         default_models_expand_depth: 0,
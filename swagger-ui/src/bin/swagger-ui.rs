@@ -0,0 +1,131 @@
+//! Command line tool for swagger-ui sites, see `swagger-ui help`.
+
+use std::path::{Component, Path, PathBuf};
+use std::process::ExitCode;
+use swagger_ui::{Bytes, Config, Options, Spec};
+
+const USAGE: &str = "Usage: swagger-ui <command> [arguments]
+
+Commands:
+  export [--config <config.json>] [--title <title>] <out-dir> <spec>...
+      Write swagger-ui, its config and the specs into <out-dir>, to be served by any static file server
+  html [--config <config.json>] [--title <title>] <out.html> <spec>
+      Write a single html file with swagger-ui and the spec inlined
+  diff [--json] <old-spec> <new-spec>
+      Print the changes between two versions of a spec, failing if any may break existing clients
+  help
+      Print this message";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("export") => export(&args[1..]),
+        Some("html") => html(&args[1..]),
+        Some("diff") => diff(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        _ => Err(USAGE.to_owned()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Arguments shared by commands generating a site
+struct SiteArgs {
+    config: Config,
+    options: Options,
+    positional: Vec<String>,
+}
+
+fn site_args(args: &[String]) -> Result<SiteArgs, String> {
+    let mut site = SiteArgs {
+        config: Config::default(),
+        options: Options::default(),
+        positional: vec![],
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value for {}\n\n{}", arg, USAGE));
+        match arg.as_str() {
+            "--config" => {
+                let path = value()?;
+                let content = std::fs::read(path).map_err(|err| format!("failed to read {}: {}", path, err))?;
+                site.config = serde_json::from_slice(&content).map_err(|err| format!("failed to parse {}: {}", path, err))?;
+            }
+            "--title" => site.options.theme.title = Some(value()?.clone().into()),
+            flag if flag.starts_with("--") => return Err(format!("unknown option {}\n\n{}", flag, USAGE)),
+            _ => site.positional.push(arg.clone()),
+        }
+    }
+    Ok(site)
+}
+
+fn read_spec(path: &str) -> Result<Spec, String> {
+    let content = std::fs::read(path).map_err(|err| format!("failed to read {}: {}", path, err))?;
+    // Relative paths are kept, so specs with the same file name in different directories don't collide
+    let relative = Path::new(path).components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    let name = if relative {
+        Some(path.trim_start_matches("./").to_owned())
+    } else {
+        Path::new(path).file_name().map(|name| name.to_string_lossy().into_owned())
+    };
+    let name = name.ok_or_else(|| format!("invalid spec path {}", path))?;
+    Ok(Spec { name: name.into(), content: Bytes::from(content) })
+}
+
+fn export(args: &[String]) -> Result<(), String> {
+    let site = site_args(args)?;
+    let (out, specs) = match site.positional.split_first() {
+        Some((out, specs)) if !specs.is_empty() => (PathBuf::from(out), specs),
+        _ => return Err(USAGE.to_owned()),
+    };
+    let specs = specs.iter().map(|path| read_spec(path)).collect::<Result<Vec<_>, _>>()?;
+    swagger_ui::export::export_dir(&out, &specs, &site.config, &site.options)
+        .map_err(|err| format!("failed to export to {}: {}", out.display(), err))
+}
+
+fn html(args: &[String]) -> Result<(), String> {
+    let site = site_args(args)?;
+    let (out, spec) = match site.positional.as_slice() {
+        [out, spec] => (out, read_spec(spec)?),
+        _ => return Err(USAGE.to_owned()),
+    };
+    let html = swagger_ui::export::export_html(&spec, &site.config, &site.options)
+        .map_err(|err| format!("failed to parse {}: {}", spec.name, err))?;
+    std::fs::write(out, html).map_err(|err| format!("failed to write {}: {}", out, err))
+}
+
+fn diff(args: &[String]) -> Result<(), String> {
+    let json = args.iter().any(|arg| arg == "--json");
+    let (old, new) = match args.iter().filter(|arg| *arg != "--json").collect::<Vec<_>>().as_slice() {
+        [old, new] if !old.starts_with("--") && !new.starts_with("--") => (read_spec(old)?, read_spec(new)?),
+        _ => return Err(USAGE.to_owned()),
+    };
+    let diff = old.diff(&new).map_err(|err| format!("failed to parse specs: {}", err))?;
+    if json {
+        let changes = diff.changes.iter()
+            .map(|change| serde_json::json!({
+                "location": change.location,
+                "pointer": change.pointer,
+                "change": change.kind.to_string(),
+                "breaking": change.breaking,
+            }))
+            .collect::<Vec<_>>();
+        println!("{}", serde_json::to_string_pretty(&changes).expect("serializing a json value can't fail"));
+    } else {
+        for change in &diff.changes {
+            println!("{}", change);
+        }
+    }
+    match diff.breaking().count() {
+        0 => Ok(()),
+        breaking => Err(format!("{} breaking change(s)", breaking)),
+    }
+}
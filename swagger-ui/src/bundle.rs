@@ -0,0 +1,347 @@
+//! Bundling of multi-file specs, enabled with the `bundle` feature.
+//!
+//! External `$ref`s (`./schemas/pet.yaml`, `common.json#/components/parameters/Id`)
+//! are replaced with the referenced values, so the result is a single document
+//! which can be served as one `Spec`. References which would recurse forever when
+//! inlined (e.g. a tree node schema referencing itself) are moved to root
+//! `components/schemas` (or `definitions` for Swagger 2.0) and referenced locally.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use percent_encoding::percent_decode_str;
+use serde_json::{Map, Value};
+use crate::{Bytes, ParseError, Spec};
+
+/// Error returned by [`bundle_file`]
+#[derive(Debug)]
+pub enum BundleError {
+    /// Failed to read a spec file
+    Io { path: PathBuf, error: std::io::Error },
+    /// Failed to parse a spec file
+    Parse { path: PathBuf, error: ParseError },
+    /// Reference points to a missing value
+    UnresolvedRef { path: PathBuf, reference: String },
+    /// Value which recursive references are moved into is not an object, e.g. the root or `components`
+    NotAnObject { path: PathBuf, pointer: String },
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::Io { path, error } => write!(f, "failed to read {}: {}", path.display(), error),
+            BundleError::Parse { path, error } => write!(f, "failed to parse {}: {}", path.display(), error),
+            BundleError::UnresolvedRef { path, reference } => write!(f, "unresolved reference {:?} in {}", reference, path.display()),
+            BundleError::NotAnObject { path, pointer } => write!(f, "value at {:?} in {} is not an object", pointer, path.display()),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+/// Loads the spec at `path` and inlines all `$ref`s to other files, relative to the referencing file.
+///
+/// The result is serialized as JSON, its name is the root file name with a `.json` extension.
+pub fn bundle_file(path: impl AsRef<Path>) -> Result<Spec, BundleError> {
+    let path = path.as_ref();
+    let root_path = canonicalize(path)?;
+    let mut bundler = Bundler {
+        root: root_path.clone(),
+        files: HashMap::new(),
+        stack: vec![],
+        hoisted: BTreeMap::new(),
+    };
+
+    let root = bundler.load(&root_path)?.clone();
+    let mut bundled = bundler.resolve(&root, &root_path)?;
+
+    // Hoisted values may hoist more values while being resolved
+    let mut done = 0;
+    while done < bundler.hoisted.len() {
+        let (target, name) = bundler.hoisted.iter()
+            .map(|(target, name)| (target.clone(), name.clone()))
+            .nth(done)
+            .unwrap();
+        let value = bundler.target(&target)?;
+        bundler.stack.push(target.clone());
+        let value = bundler.resolve(&value, &target.0)?;
+        bundler.stack.pop();
+        definitions(&mut bundled, &root_path)?.insert(name, value);
+        done += 1;
+    }
+
+    let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("openapi");
+    Ok(Spec {
+        name: Cow::Owned(format!("{}.json", name)),
+        content: Bytes::from(serde_json::to_vec(&bundled).expect("serializing json value can't fail")),
+    })
+}
+
+struct Bundler {
+    root: PathBuf,
+    files: HashMap<PathBuf, Value>,
+    /// References which are being inlined
+    stack: Vec<(PathBuf, String)>,
+    /// References moved to root definitions, with their new names
+    hoisted: BTreeMap<(PathBuf, String), String>,
+}
+
+impl Bundler {
+    fn load(&mut self, path: &Path) -> Result<&Value, BundleError> {
+        if !self.files.contains_key(path) {
+            let content = std::fs::read(path).map_err(|error| BundleError::Io { path: path.to_owned(), error })?;
+            let spec = Spec {
+                name: Cow::Owned(path.to_string_lossy().into_owned()),
+                content: Bytes::from(content),
+            };
+            let value = spec.parse().map_err(|error| BundleError::Parse { path: path.to_owned(), error })?;
+            self.files.insert(path.to_owned(), value);
+        }
+        Ok(&self.files[path])
+    }
+
+    fn target(&mut self, (path, fragment): &(PathBuf, String)) -> Result<Value, BundleError> {
+        // `$ref`s are URIs: fragments are percent-encoded JSON pointers, e.g. `#/paths/~1pets~1%7Bid%7D`
+        self.load(path)?
+            .pointer(&percent_decode_str(fragment).decode_utf8_lossy())
+            .cloned()
+            .ok_or_else(|| BundleError::UnresolvedRef { path: path.clone(), reference: format!("#{}", fragment) })
+    }
+
+    fn resolve(&mut self, value: &Value, base: &Path) -> Result<Value, BundleError> {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    if let Some(resolved) = self.resolve_ref(reference, map, base)? {
+                        return Ok(resolved);
+                    }
+                }
+                let mut out = Map::new();
+                for (key, value) in map {
+                    out.insert(key.clone(), self.resolve(value, base)?);
+                }
+                Ok(Value::Object(out))
+            }
+            Value::Array(items) => Ok(Value::Array(
+                items.iter().map(|v| self.resolve(v, base)).collect::<Result<_, _>>()?
+            )),
+            other => Ok(other.clone()),
+        }
+    }
+
+    /// Returns `None` if the reference has to be kept as is
+    fn resolve_ref(&mut self, reference: &str, map: &Map<String, Value>, base: &Path) -> Result<Option<Value>, BundleError> {
+        let (file, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+        if file.contains("://") || (file.is_empty() && base == self.root) {
+            return Ok(None);
+        }
+        let path = if file.is_empty() {
+            base.to_owned()
+        } else {
+            let joined = base.parent().unwrap_or_else(|| Path::new("")).join(&*percent_decode_str(file).decode_utf8_lossy());
+            canonicalize(&joined)?
+        };
+        let target = (path, fragment.to_owned());
+
+        if target.0 == self.root && !fragment.is_empty() {
+            return Ok(Some(ref_object(format!("#{}", fragment), map)));
+        }
+        if self.stack.contains(&target) || self.hoisted.contains_key(&target) {
+            let name = self.hoist(&target);
+            return Ok(Some(ref_object(format!("#{}/{}", self.definitions_pointer(), name), map)));
+        }
+
+        let value = self.target(&target)?;
+        self.stack.push(target.clone());
+        let mut resolved = self.resolve(&value, &target.0)?;
+        self.stack.pop();
+
+        // Keep siblings of `$ref`, e.g. `description`
+        if let Value::Object(resolved) = &mut resolved {
+            for (key, value) in map.iter().filter(|(k, _)| *k != "$ref") {
+                resolved.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+        Ok(Some(resolved))
+    }
+
+    fn hoist(&mut self, target: &(PathBuf, String)) -> String {
+        if let Some(name) = self.hoisted.get(target) {
+            return name.clone();
+        }
+        let base = target.1.rsplit('/').next().filter(|s| !s.is_empty())
+            .map(|s| percent_decode_str(s).decode_utf8_lossy().replace("~1", "/").replace("~0", "~"))
+            .or_else(|| target.0.file_stem().map(|s| s.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "Schema".to_owned());
+        let mut name = base.clone();
+        let mut i = 1;
+        while self.hoisted.values().any(|n| *n == name) || self.root_definition_exists(&name) {
+            i += 1;
+            name = format!("{}{}", base, i);
+        }
+        self.hoisted.insert(target.clone(), name.clone());
+        name
+    }
+
+    fn is_swagger2(&self) -> bool {
+        self.files[&self.root].get("swagger").is_some()
+    }
+
+    fn definitions_pointer(&self) -> &'static str {
+        if self.is_swagger2() { "/definitions" } else { "/components/schemas" }
+    }
+
+    fn root_definition_exists(&self, name: &str) -> bool {
+        self.files[&self.root]
+            .pointer(self.definitions_pointer())
+            .and_then(|d| d.get(name))
+            .is_some()
+    }
+}
+
+fn canonicalize(path: &Path) -> Result<PathBuf, BundleError> {
+    std::fs::canonicalize(path).map_err(|error| BundleError::Io { path: path.to_owned(), error })
+}
+
+fn ref_object(reference: String, map: &Map<String, Value>) -> Value {
+    let mut out = map.clone();
+    out.insert("$ref".to_owned(), Value::String(reference));
+    Value::Object(out)
+}
+
+/// Returns root `components/schemas` (or `definitions` for Swagger 2.0) of the spec at `path`, created if missing
+fn definitions<'a>(document: &'a mut Value, path: &Path) -> Result<&'a mut Map<String, Value>, BundleError> {
+    let not_an_object = |pointer: &str| BundleError::NotAnObject { path: path.to_owned(), pointer: pointer.to_owned() };
+    let root = document.as_object_mut().ok_or_else(|| not_an_object(""))?;
+    let (holder, pointer) = if root.contains_key("swagger") {
+        (root, "/definitions")
+    } else {
+        let components = root.entry("components")
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or_else(|| not_an_object("/components"))?;
+        (components, "/components/schemas")
+    };
+    let key = pointer.rsplit('/').next().unwrap_or_default();
+    holder.entry(key)
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+        .ok_or_else(|| not_an_object(pointer))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use serde_json::Value;
+    use super::*;
+
+    fn fixture() -> Value {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/split/openapi.yaml");
+        let spec = bundle_file(path).unwrap();
+        assert_eq!(spec.name, "openapi.json");
+        spec.parse().unwrap()
+    }
+
+    fn refs(value: &Value, out: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(r)) = map.get("$ref") {
+                    out.push(r.clone());
+                }
+                map.values().for_each(|v| refs(v, out));
+            }
+            Value::Array(items) => items.iter().for_each(|v| refs(v, out)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn inlines_external_refs() {
+        let bundled = fixture();
+        let mut all = vec![];
+        refs(&bundled, &mut all);
+        assert!(all.iter().all(|r| r.starts_with("#/")), "{:?}", all);
+
+        let pet = &bundled["paths"]["/pets"]["get"]["responses"]["200"]["content"]["application/json"]["schema"]["items"];
+        assert_eq!(pet["properties"]["name"]["type"], "string");
+        assert_eq!(pet["properties"]["category"]["properties"]["id"]["type"], "integer");
+        assert_eq!(bundled["paths"]["/pets"]["get"]["parameters"][0]["name"], "limit");
+    }
+
+    #[test]
+    fn hoists_recursive_refs() {
+        let bundled = fixture();
+        let pet = &bundled["components"]["schemas"]["pet"];
+        assert_eq!(pet["properties"]["children"]["items"]["$ref"], "#/components/schemas/pet");
+        // existing root schemas are kept
+        assert!(bundled["components"]["schemas"]["Error"].is_object());
+    }
+
+    #[test]
+    fn decodes_percent_encoded_refs() {
+        let dir = std::env::temp_dir().join(format!("swagger-ui-bundle-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("openapi.yaml"), concat!(
+            "openapi: 3.0.3\n",
+            "paths:\n",
+            "  /pets/{id}:\n",
+            "    $ref: 'other%20paths.yaml#/paths/~1pets~1%7Bid%7D'\n",
+        )).unwrap();
+        std::fs::write(dir.join("other paths.yaml"), concat!(
+            "paths:\n",
+            "  /pets/{id}:\n",
+            "    get:\n",
+            "      responses:\n",
+            "        '200':\n",
+            "          content:\n",
+            "            application/json:\n",
+            "              schema:\n",
+            "                $ref: '#/schemas/My%20Pet'\n",
+            "schemas:\n",
+            "  My Pet:\n",
+            "    type: object\n",
+        )).unwrap();
+
+        let bundled = bundle_file(dir.join("openapi.yaml")).map(|spec| spec.parse());
+        std::fs::remove_dir_all(&dir).unwrap();
+        let bundled: Value = bundled.unwrap().unwrap();
+        let schema = &bundled["paths"]["/pets/{id}"]["get"]["responses"]["200"]["content"]["application/json"]["schema"];
+        assert_eq!(schema["type"], "object");
+    }
+
+    #[test]
+    fn reports_invalid_components() {
+        let dir = std::env::temp_dir().join(format!("swagger-ui-bundle-invalid-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("openapi.yaml"), concat!(
+            "openapi: 3.0.3\n",
+            "components: []\n",
+            "paths:\n",
+            "  /tree:\n",
+            "    get:\n",
+            "      responses:\n",
+            "        '200':\n",
+            "          content:\n",
+            "            application/json:\n",
+            "              schema:\n",
+            "                $ref: 'node.yaml'\n",
+        )).unwrap();
+        std::fs::write(dir.join("node.yaml"), concat!(
+            "type: object\n",
+            "properties:\n",
+            "  child:\n",
+            "    $ref: 'node.yaml'\n",
+        )).unwrap();
+
+        let err = bundle_file(dir.join("openapi.yaml")).unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(&err, BundleError::NotAnObject { pointer, .. } if pointer == "/components"), "{}", err);
+    }
+
+    #[test]
+    fn reports_missing_files() {
+        let err = bundle_file(Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/split/missing.yaml")).unwrap_err();
+        assert!(matches!(err, BundleError::Io { .. }));
+    }
+}
@@ -0,0 +1,223 @@
+//! Changelog page between two versions of a spec, enabled with the `changelog` feature.
+//!
+//! Set `Options::changelog` for the adapters to serve it at `changelog.html` under the swagger-ui mount.
+//! Added, removed and deprecated operations are listed with their summary, followed by the other
+//! changes found by [`diff`](crate::diff), breaking ones first.
+
+use std::cmp::Ordering;
+use serde_json::Value;
+use crate::diff::{diff_values, Change, ChangeKind, Diff};
+use crate::index::escape_html;
+use crate::versions::natural_cmp;
+use crate::{Bytes, ParseError, Spec, Versions};
+
+/// Path of the changelog page under the swagger-ui mount
+pub const CHANGELOG_PATH: &str = "changelog.html";
+
+/// Changelog between two versions of a spec, rendered once when created
+#[derive(Debug, Clone)]
+pub struct Changelog {
+    diff: Diff,
+    html: Bytes,
+}
+
+impl Changelog {
+    /// Create the changelog from `old` to `new`, named by their versions, e.g. `v1` and `v2`
+    pub fn new(old_version: &str, old: &Spec, new_version: &str, new: &Spec) -> Result<Self, ParseError> {
+        let (old, new) = (old.parse()?, new.parse()?);
+        let diff = diff_values(&old, &new);
+        let html = Bytes::from(render(old_version, &old, new_version, &new, &diff));
+        Ok(Self { diff, html })
+    }
+
+    /// Create the changelog from the greatest version before the latest one to the latest one.
+    /// `None` if no version is before the latest one.
+    pub fn from_versions(versions: &Versions) -> Result<Option<Self>, ParseError> {
+        let specs = versions.specs();
+        let Some(((new_version, new), others)) = specs.split_first() else {
+            return Ok(None);
+        };
+        // Other versions are sorted from the greatest, which can be after a latest set with `with_latest`
+        match others.iter().find(|(version, _)| natural_cmp(version, new_version) == Ordering::Less) {
+            Some((old_version, old)) => Self::new(old_version, old, new_version, new).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Changes listed in the changelog
+    pub fn diff(&self) -> &Diff {
+        &self.diff
+    }
+
+    /// Content of the changelog page
+    pub fn html(&self) -> Bytes {
+        self.html.clone()
+    }
+}
+
+fn render(old_version: &str, old: &Value, new_version: &str, new: &Value, diff: &Diff) -> String {
+    let operations = |kind: ChangeKind, spec: &Value| diff.changes.iter()
+        .filter(|change| change.kind == kind)
+        .map(|change| operation_item(change, spec))
+        .collect::<Vec<_>>();
+    let mut others = diff.changes.iter()
+        .filter(|change| !matches!(change.kind, ChangeKind::OperationAdded | ChangeKind::OperationRemoved | ChangeKind::OperationDeprecated))
+        .collect::<Vec<_>>();
+    others.sort_by_key(|change| !change.breaking);
+    let others = others.into_iter().map(change_item).collect::<Vec<_>>();
+
+    let mut sections = String::new();
+    for (title, items) in [
+        ("Added operations", operations(ChangeKind::OperationAdded, new)),
+        ("Removed operations", operations(ChangeKind::OperationRemoved, old)),
+        ("Deprecated operations", operations(ChangeKind::OperationDeprecated, new)),
+        ("Other changes", others),
+    ] {
+        if !items.is_empty() {
+            sections.push_str(&format!("    <h2>{}</h2>\n    <ul>\n{}    </ul>\n", title, items.concat()));
+        }
+    }
+    if sections.is_empty() {
+        sections.push_str("    <p>No changes.</p>\n");
+    }
+    let breaking = diff.breaking().count();
+    let summary = match breaking {
+        0 => "No breaking changes.".to_owned(),
+        1 => "1 breaking change.".to_owned(),
+        n => format!("{} breaking changes.", n),
+    };
+
+    format!(r##"<!DOCTYPE html>
+<html lang="en">
+  <head>
+    <meta charset="UTF-8">
+    <title>{title}</title>
+    <style>
+      body {{ margin: 0 auto; max-width: 60em; padding: 1em; font-family: sans-serif; color: #3b4151; }}
+      li {{ margin: 0.3em 0; }}
+      .breaking {{ color: #f93e3e; font-weight: bold; }}
+    </style>
+  </head>
+  <body>
+    <h1>{title}</h1>
+    <p>{summary} <a href="./index.html">API documentation</a></p>
+{sections}  </body>
+</html>
+"##,
+        title = escape_html(&format!("Changes from {} to {}", old_version, new_version)),
+        summary = summary,
+        sections = sections,
+    )
+}
+
+/// Lists an operation with its summary, looked up in `spec`
+fn operation_item(change: &Change, spec: &Value) -> String {
+    let summary = change.location.split_once(' ')
+        .and_then(|(method, path)| spec.get("paths")?.get(path)?.get(method.to_lowercase())?.get("summary")?.as_str())
+        .map(|summary| format!(" {}", escape_html(summary)))
+        .unwrap_or_default();
+    format!("      <li{}><code>{}</code>{}</li>\n", class(change), escape_html(&change.location), summary)
+}
+
+fn change_item(change: &Change) -> String {
+    let pointer = match change.pointer.as_str() {
+        "" => String::new(),
+        pointer => format!(" at <code>{}</code>", escape_html(pointer)),
+    };
+    let severity = if change.breaking { "breaking: " } else { "" };
+    format!("      <li{}>{}<code>{}</code>{}: {}</li>\n",
+            class(change), severity, escape_html(&change.location), pointer, escape_html(&change.kind.to_string()))
+}
+
+fn class(change: &Change) -> &'static str {
+    if change.breaking { r#" class="breaking""# } else { "" }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    fn spec(paths: Value) -> Spec {
+        let document = json!({"openapi": "3.0.3", "info": {"title": "pets", "version": "1"}, "paths": paths});
+        Spec { name: "openapi.json".into(), content: Bytes::from(serde_json::to_vec(&document).unwrap()) }
+    }
+
+    #[test]
+    fn lists_operation_changes() {
+        let responses = json!({"200": {"description": "ok"}});
+        let old = spec(json!({
+            "/pets": {"get": {"summary": "List pets", "responses": responses}},
+            "/pets/{id}": {"delete": {"summary": "Delete a pet", "responses": responses}},
+        }));
+        let new = spec(json!({
+            "/pets": {
+                "get": {"summary": "List pets", "deprecated": true, "responses": responses},
+                "post": {"summary": "Add a <pet>", "responses": responses},
+            },
+        }));
+        let versions = Versions::new().with_version("v1", old).with_version("v2", new);
+        let changelog = Changelog::from_versions(&versions).unwrap().unwrap();
+        let html = String::from_utf8(changelog.html().to_vec()).unwrap();
+
+        assert!(html.contains("<title>Changes from v1 to v2</title>"));
+        assert!(html.contains("1 breaking change."));
+        assert!(html.contains("<h2>Added operations</h2>\n    <ul>\n      <li><code>POST /pets</code> Add a &lt;pet&gt;</li>"));
+        assert!(html.contains(r#"<h2>Removed operations</h2>
+    <ul>
+      <li class="breaking"><code>DELETE /pets/{id}</code> Delete a pet</li>"#));
+        assert!(html.contains("<h2>Deprecated operations</h2>\n    <ul>\n      <li><code>GET /pets</code> List pets</li>"));
+        assert!(!html.contains("Other changes"));
+        assert_eq!(changelog.diff().changes.len(), 3);
+    }
+
+    #[test]
+    fn renders_other_changes() {
+        let old = spec(json!({"/pets": {"get": {"responses": {"200": {"description": "ok"}}}}}));
+        let new = spec(json!({"/pets": {"get": {
+            "parameters": [{"name": "limit", "in": "query", "required": true, "schema": {"type": "integer"}}],
+            "responses": {"200": {"description": "ok"}, "404": {"description": "not found"}},
+        }}}));
+        let html = String::from_utf8(Changelog::new("v1", &old, "v2", &new).unwrap().html().to_vec()).unwrap();
+        let breaking = html.find("breaking: <code>GET /pets query parameter limit</code>: required parameter added").unwrap();
+        let added = html.find("<li><code>GET /pets response 404</code>: response 404 added</li>").unwrap();
+        assert!(breaking < added);
+
+        let html = String::from_utf8(Changelog::new("v1", &old, "v1", &old).unwrap().html().to_vec()).unwrap();
+        assert!(html.contains("<p>No changes.</p>"));
+        assert!(Changelog::from_versions(&Versions::new().with_version("v1", old)).unwrap().is_none());
+    }
+
+    #[test]
+    fn compares_with_the_version_before_latest() {
+        let v1 = spec(json!({}));
+        let v2 = spec(json!({"/pets": {"get": {"responses": {"200": {"description": "ok"}}}}}));
+        let versions = Versions::new()
+            .with_version("v1", v1.clone())
+            .with_version("v2", v2)
+            .with_version("v10", v1)
+            .with_latest("v2");
+        let html = String::from_utf8(Changelog::from_versions(&versions).unwrap().unwrap().html().to_vec()).unwrap();
+        assert!(html.contains("<title>Changes from v1 to v2</title>"));
+        assert!(html.contains("<h2>Added operations</h2>"));
+
+        let versions = Versions::new()
+            .with_version("v1", spec(json!({})))
+            .with_version("v2", spec(json!({})))
+            .with_latest("v1");
+        assert!(Changelog::from_versions(&versions).unwrap().is_none());
+    }
+
+    #[test]
+    fn served_by_options() {
+        let old = spec(json!({}));
+        let options = crate::Options {
+            changelog: Some(Changelog::new("v1", &old, "v2", &old).unwrap()),
+            ..Default::default()
+        };
+        let file = options.file_response(CHANGELOG_PATH).unwrap();
+        assert!(file.headers.iter().any(|(name, _)| *name == "Content-Security-Policy"));
+        assert!(options.paths().contains(&CHANGELOG_PATH.to_owned()));
+        assert!(crate::Options::default().file(CHANGELOG_PATH).is_none());
+    }
+}
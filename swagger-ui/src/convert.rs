@@ -0,0 +1,551 @@
+//! Conversion of Swagger 2.0 specs to OpenAPI 3.0, enabled with the `convert` feature.
+//!
+//! Servers are built from `host`, `basePath` and `schemes`, body and form parameters become
+//! request bodies with the `consumes` media types, response schemas get the `produces` ones,
+//! `definitions`, `parameters`, `responses` and `securityDefinitions` move to `components`,
+//! and references are rewritten accordingly. Vendor extensions are kept.
+
+use std::fmt;
+use serde_json::{json, Map, Value};
+use crate::{Bytes, ParseError, Spec};
+
+/// OpenAPI version of converted specs
+const OPENAPI_VERSION: &str = "3.0.3";
+
+/// Error returned by [`to_openapi3`]
+#[derive(Debug)]
+pub enum ConvertError {
+    /// Content is neither valid JSON nor valid YAML
+    Parse(ParseError),
+    /// Document is neither Swagger 2.0 nor OpenAPI 3
+    UnknownVersion,
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertError::Parse(err) => write!(f, "failed to parse spec: {}", err),
+            ConvertError::UnknownVersion => write!(f, "spec is neither Swagger 2.0 nor OpenAPI 3"),
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+impl From<ParseError> for ConvertError {
+    fn from(err: ParseError) -> Self {
+        ConvertError::Parse(err)
+    }
+}
+
+impl Spec {
+    /// Converts the spec to OpenAPI 3.0, see [`to_openapi3`](crate::convert::to_openapi3)
+    pub fn to_openapi3(&self) -> Result<Spec, ConvertError> {
+        to_openapi3(self)
+    }
+}
+
+/// Converts a Swagger 2.0 spec to OpenAPI 3.0, keeping its name and format (JSON or YAML).
+/// OpenAPI 3 specs are returned unchanged.
+pub fn to_openapi3(spec: &Spec) -> Result<Spec, ConvertError> {
+    let document = spec.parse()?;
+    if document.get("openapi").and_then(Value::as_str).is_some_and(|version| version.starts_with('3')) {
+        return Ok(spec.clone());
+    }
+    if document.get("swagger").and_then(Value::as_str) != Some("2.0") {
+        return Err(ConvertError::UnknownVersion);
+    }
+    let converted = convert_value(&document);
+    let content = if spec.is_yaml() {
+        serde_yaml::to_string(&converted).expect("serializing a json value can't fail").into_bytes()
+    } else {
+        serde_json::to_vec_pretty(&converted).expect("serializing a json value can't fail")
+    };
+    Ok(Spec { name: spec.name.clone(), content: Bytes::from(content) })
+}
+
+/// Converts an already parsed Swagger 2.0 document
+pub fn convert_value(swagger: &Value) -> Value {
+    let mut converter = Converter { swagger, components: Map::new() };
+    let mut openapi = Map::new();
+    openapi.insert("openapi".to_owned(), json!(OPENAPI_VERSION));
+    for (key, value) in swagger.as_object().into_iter().flatten() {
+        match key.as_str() {
+            "swagger" | "host" | "basePath" | "schemes" | "consumes" | "produces" => {}
+            "paths" => {
+                let paths = value.as_object().into_iter().flatten()
+                    .map(|(path, item)| (path.clone(), converter.path_item(item)))
+                    .collect();
+                openapi.insert(key.clone(), Value::Object(paths));
+            }
+            "definitions" => converter.component("schemas", value, |_, schema| convert_schema(schema)),
+            "parameters" => {
+                for (name, param) in value.as_object().into_iter().flatten() {
+                    match param.get("in").and_then(Value::as_str) {
+                        Some("body") => {
+                            let body = converter.body(param, None);
+                            converter.insert("requestBodies", name, body);
+                        }
+                        Some("formData") => {
+                            let body = converter.form(&[param], None);
+                            converter.insert("requestBodies", name, body);
+                        }
+                        _ => converter.insert("parameters", name, convert_parameter(param)),
+                    }
+                }
+            }
+            "responses" => converter.component("responses", value, |converter, response| converter.response(response, None)),
+            "securityDefinitions" => converter.component("securitySchemes", value, |_, scheme| convert_security_scheme(scheme)),
+            _ => {
+                openapi.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    openapi.insert("servers".to_owned(), servers(swagger));
+    if !converter.components.is_empty() {
+        openapi.insert("components".to_owned(), Value::Object(converter.components));
+    }
+    let mut openapi = Value::Object(openapi);
+    rewrite_refs(&mut openapi);
+    openapi
+}
+
+struct Converter<'a> {
+    swagger: &'a Value,
+    components: Map<String, Value>,
+}
+
+impl<'a> Converter<'a> {
+    fn component(&mut self, kind: &str, values: &Value, convert: impl Fn(&Self, &Value) -> Value) {
+        for (name, value) in values.as_object().into_iter().flatten() {
+            let converted = convert(self, value);
+            self.insert(kind, name, converted);
+        }
+    }
+
+    fn insert(&mut self, kind: &str, name: &str, value: Value) {
+        let components = self.components.entry(kind.to_owned()).or_insert_with(|| json!({}));
+        components[name] = value;
+    }
+
+    fn path_item(&self, item: &Value) -> Value {
+        let item = match item.as_object() {
+            Some(item) => item,
+            None => return item.clone(),
+        };
+        let shared = item.get("parameters").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+        let mut converted = Map::new();
+        for (key, value) in item {
+            match key.as_str() {
+                "get" | "put" | "post" | "delete" | "options" | "head" | "patch" => {
+                    converted.insert(key.clone(), self.operation(value, shared));
+                }
+                // Body and form parameters are moved to the request body of each operation
+                "parameters" => {
+                    let params = shared.iter()
+                        .filter(|param| !is_body(self.param(param)))
+                        .map(convert_parameter)
+                        .collect::<Vec<_>>();
+                    if !params.is_empty() {
+                        converted.insert(key.clone(), Value::Array(params));
+                    }
+                }
+                _ => {
+                    converted.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        Value::Object(converted)
+    }
+
+    fn operation(&self, operation: &Value, shared: &[Value]) -> Value {
+        let operation_map = match operation.as_object() {
+            Some(operation) => operation,
+            None => return operation.clone(),
+        };
+        let own = operation.get("parameters").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+        let overridden = |param: &&Value| own.iter().any(|own| {
+            let (own, param) = (self.param(own), self.param(param));
+            own.get("name") == param.get("name") && own.get("in") == param.get("in")
+        });
+        let body_params = shared.iter().filter(|param| !overridden(param))
+            .chain(own)
+            .filter(|param| is_body(self.param(param)))
+            .collect::<Vec<_>>();
+
+        let mut converted = Map::new();
+        for (key, value) in operation_map {
+            match key.as_str() {
+                "consumes" | "produces" | "schemes" => {}
+                "parameters" => {
+                    let params = own.iter()
+                        .filter(|param| !is_body(self.param(param)))
+                        .map(convert_parameter)
+                        .collect::<Vec<_>>();
+                    if !params.is_empty() {
+                        converted.insert(key.clone(), Value::Array(params));
+                    }
+                }
+                "responses" => {
+                    let responses = value.as_object().into_iter().flatten()
+                        .map(|(status, response)| (status.clone(), self.response(response, Some(operation))))
+                        .collect();
+                    converted.insert(key.clone(), Value::Object(responses));
+                }
+                _ => {
+                    converted.insert(key.clone(), value.clone());
+                }
+            }
+        }
+
+        let body = match body_params.as_slice() {
+            [] => None,
+            // A referenced body parameter is a reusable request body
+            [param] if param.get("$ref").is_some() => {
+                let name = param["$ref"].as_str().and_then(|r| r.rsplit('/').next()).unwrap_or_default();
+                Some(json!({ "$ref": format!("#/components/requestBodies/{}", name) }))
+            }
+            [param] if self.param(param).get("in").and_then(Value::as_str) == Some("body") => Some(self.body(param, Some(operation))),
+            params => {
+                let params = params.iter().map(|param| self.param(param)).collect::<Vec<_>>();
+                Some(self.form(&params, Some(operation)))
+            }
+        };
+        if let Some(body) = body {
+            converted.insert("requestBody".to_owned(), body);
+        }
+        Value::Object(converted)
+    }
+
+    /// Request body of a `body` parameter
+    fn body(&self, param: &Value, operation: Option<&Value>) -> Value {
+        let schema = param.get("schema").map(convert_schema).unwrap_or_else(|| json!({}));
+        let mut body = Map::new();
+        copy(param, &mut body, &["description"]);
+        body.insert("content".to_owned(), self.content(operation, "consumes", "application/json", &schema, None));
+        copy(param, &mut body, &["required"]);
+        extensions(param, &mut body);
+        Value::Object(body)
+    }
+
+    /// Request body of `formData` parameters, `multipart/form-data` if a file is uploaded
+    fn form(&self, params: &[&Value], operation: Option<&Value>) -> Value {
+        let mut properties = Map::new();
+        let mut required = vec![];
+        for param in params {
+            let name = param.get("name").and_then(Value::as_str).unwrap_or_default();
+            let mut schema = parameter_schema(param);
+            if let Some(description) = param.get("description") {
+                schema["description"] = description.clone();
+            }
+            properties.insert(name.to_owned(), schema);
+            if param.get("required").and_then(Value::as_bool).unwrap_or(false) {
+                required.push(json!(name));
+            }
+        }
+        let mut schema = json!({ "type": "object", "properties": properties });
+        if !required.is_empty() {
+            schema["required"] = Value::Array(required);
+        }
+        let upload = params.iter().any(|param| param.get("type").and_then(Value::as_str) == Some("file"));
+        let default = if upload { "multipart/form-data" } else { "application/x-www-form-urlencoded" };
+        json!({ "content": self.content(operation, "consumes", default, &schema, None) })
+    }
+
+    fn response(&self, response: &Value, operation: Option<&Value>) -> Value {
+        let response_map = match response.as_object() {
+            Some(response) if response.get("$ref").is_none() => response,
+            _ => return response.clone(),
+        };
+        let mut converted = Map::new();
+        converted.insert("description".to_owned(), response.get("description").cloned().unwrap_or_else(|| json!("")));
+        if let Some(headers) = response.get("headers").and_then(Value::as_object) {
+            let headers = headers.iter()
+                .map(|(name, header)| {
+                    let mut converted = Map::new();
+                    copy(header, &mut converted, &["description"]);
+                    converted.insert("schema".to_owned(), parameter_schema(header));
+                    (name.clone(), Value::Object(converted))
+                })
+                .collect();
+            converted.insert("headers".to_owned(), Value::Object(headers));
+        }
+        if let Some(schema) = response.get("schema") {
+            let content = self.content(operation, "produces", "application/json", &convert_schema(schema), response.get("examples"));
+            converted.insert("content".to_owned(), content);
+        }
+        for (key, value) in response_map {
+            if key.starts_with("x-") {
+                converted.insert(key.clone(), value.clone());
+            }
+        }
+        Value::Object(converted)
+    }
+
+    /// Content with `schema` for the media types the operation or the document `consumes` or `produces`
+    fn content(&self, operation: Option<&Value>, media_types: &str, default: &str, schema: &Value, examples: Option<&Value>) -> Value {
+        let declared = operation.and_then(|operation| operation.get(media_types))
+            .or_else(|| self.swagger.get(media_types))
+            .and_then(Value::as_array)
+            .filter(|media_types| !media_types.is_empty());
+        let media_types = match declared {
+            Some(media_types) => media_types.iter().filter_map(Value::as_str).collect(),
+            None => vec![default],
+        };
+        let content = media_types.into_iter()
+            .map(|media_type| {
+                let mut media = json!({ "schema": schema });
+                if let Some(example) = examples.and_then(|examples| examples.get(media_type)) {
+                    media["example"] = example.clone();
+                }
+                (media_type.to_owned(), media)
+            })
+            .collect();
+        Value::Object(content)
+    }
+
+    /// Follows a reference to a reusable parameter
+    fn param<'p>(&'p self, param: &'p Value) -> &'p Value {
+        param.get("$ref").and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix('#'))
+            .and_then(|pointer| self.swagger.pointer(pointer))
+            .unwrap_or(param)
+    }
+}
+
+fn is_body(param: &Value) -> bool {
+    matches!(param.get("in").and_then(Value::as_str), Some("body" | "formData"))
+}
+
+/// Servers from `host`, `basePath` and `schemes`, scheme-relative without `schemes`
+fn servers(swagger: &Value) -> Value {
+    let base_path = swagger.get("basePath").and_then(Value::as_str).unwrap_or_default();
+    let host = match swagger.get("host").and_then(Value::as_str) {
+        Some(host) => host,
+        None => return json!([{ "url": if base_path.is_empty() { "/" } else { base_path } }]),
+    };
+    let schemes = swagger.get("schemes").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+    if schemes.is_empty() {
+        return json!([{ "url": format!("//{}{}", host, base_path) }]);
+    }
+    schemes.iter()
+        .filter_map(Value::as_str)
+        .map(|scheme| json!({ "url": format!("{}://{}{}", scheme, host, base_path) }))
+        .collect()
+}
+
+/// Converts a non-body parameter, moving its type to `schema` and `collectionFormat` to `style`
+fn convert_parameter(param: &Value) -> Value {
+    if !param.is_object() || param.get("$ref").is_some() {
+        return param.clone();
+    }
+    let mut converted = Map::new();
+    copy(param, &mut converted, &["name", "in", "description", "required", "allowEmptyValue", "deprecated"]);
+    let location = param.get("in").and_then(Value::as_str).unwrap_or_default();
+    match param.get("collectionFormat").and_then(Value::as_str) {
+        Some("multi") => {
+            converted.insert("style".to_owned(), json!("form"));
+            converted.insert("explode".to_owned(), json!(true));
+        }
+        Some("ssv") if location == "query" => {
+            converted.insert("style".to_owned(), json!("spaceDelimited"));
+            converted.insert("explode".to_owned(), json!(false));
+        }
+        Some("pipes") if location == "query" => {
+            converted.insert("style".to_owned(), json!("pipeDelimited"));
+            converted.insert("explode".to_owned(), json!(false));
+        }
+        // `csv` is the default of 2.0 but not of 3.0 query parameters, other formats have no 3.0 equivalent
+        _ if param.get("type").and_then(Value::as_str) == Some("array") => {
+            converted.insert("style".to_owned(), json!(if location == "query" { "form" } else { "simple" }));
+            converted.insert("explode".to_owned(), json!(false));
+        }
+        _ => {}
+    }
+    converted.insert("schema".to_owned(), parameter_schema(param));
+    extensions(param, &mut converted);
+    Value::Object(converted)
+}
+
+/// Schema of a 2.0 parameter, header or items object, which have their type inline
+fn parameter_schema(param: &Value) -> Value {
+    const SCHEMA_FIELDS: [&str; 15] = [
+        "type", "format", "default", "maximum", "exclusiveMaximum", "minimum", "exclusiveMinimum", "maxLength",
+        "minLength", "pattern", "maxItems", "minItems", "uniqueItems", "enum", "multipleOf",
+    ];
+    let mut schema = Map::new();
+    copy(param, &mut schema, &SCHEMA_FIELDS);
+    if let Some(items) = param.get("items") {
+        schema.insert("items".to_owned(), parameter_schema(items));
+    }
+    if param.get("x-nullable").and_then(Value::as_bool).unwrap_or(false) {
+        schema.insert("nullable".to_owned(), json!(true));
+    }
+    convert_schema(&Value::Object(schema))
+}
+
+/// Converts 2.0 specifics of a schema and its sub-schemas: `file` type, `x-nullable` and `discriminator`
+fn convert_schema(schema: &Value) -> Value {
+    let schema_map = match schema.as_object() {
+        Some(schema) => schema,
+        None => return schema.clone(),
+    };
+    let mut converted = Map::new();
+    for (key, value) in schema_map {
+        match key.as_str() {
+            "type" if value == "file" => {
+                converted.insert("type".to_owned(), json!("string"));
+                converted.insert("format".to_owned(), json!("binary"));
+            }
+            "x-nullable" => {
+                converted.insert("nullable".to_owned(), value.clone());
+            }
+            "discriminator" if value.is_string() => {
+                converted.insert(key.clone(), json!({ "propertyName": value }));
+            }
+            "properties" => {
+                let properties = value.as_object().into_iter().flatten()
+                    .map(|(name, property)| (name.clone(), convert_schema(property)))
+                    .collect();
+                converted.insert(key.clone(), Value::Object(properties));
+            }
+            "items" | "additionalProperties" | "not" => {
+                converted.insert(key.clone(), convert_schema(value));
+            }
+            "allOf" | "anyOf" | "oneOf" => {
+                converted.insert(key.clone(), value.as_array().into_iter().flatten().map(convert_schema).collect());
+            }
+            _ => {
+                converted.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    Value::Object(converted)
+}
+
+fn convert_security_scheme(scheme: &Value) -> Value {
+    let mut converted = Map::new();
+    match scheme.get("type").and_then(Value::as_str) {
+        Some("basic") => {
+            converted.insert("type".to_owned(), json!("http"));
+            converted.insert("scheme".to_owned(), json!("basic"));
+        }
+        Some("oauth2") => {
+            converted.insert("type".to_owned(), json!("oauth2"));
+            let flow = match scheme.get("flow").and_then(Value::as_str) {
+                Some("accessCode") => "authorizationCode",
+                Some("application") => "clientCredentials",
+                Some(flow) => flow,
+                None => "implicit",
+            };
+            let mut converted_flow = Map::new();
+            copy(scheme, &mut converted_flow, &["authorizationUrl", "tokenUrl"]);
+            converted_flow.insert("scopes".to_owned(), scheme.get("scopes").cloned().unwrap_or_else(|| json!({})));
+            converted.insert("flows".to_owned(), json!({ flow: converted_flow }));
+        }
+        _ => copy(scheme, &mut converted, &["type", "name", "in"]),
+    }
+    copy(scheme, &mut converted, &["description"]);
+    extensions(scheme, &mut converted);
+    Value::Object(converted)
+}
+
+/// Points references to their new location in `components`
+fn rewrite_refs(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::String(reference)) = object.get_mut("$ref") {
+                for (old, new) in [("#/definitions/", "#/components/schemas/"), ("#/parameters/", "#/components/parameters/"),
+                                   ("#/responses/", "#/components/responses/")] {
+                    if let Some(name) = reference.strip_prefix(old) {
+                        *reference = format!("{}{}", new, name);
+                        break;
+                    }
+                }
+            }
+            object.values_mut().for_each(rewrite_refs);
+        }
+        Value::Array(array) => array.iter_mut().for_each(rewrite_refs),
+        _ => {}
+    }
+}
+
+fn copy(from: &Value, to: &mut Map<String, Value>, keys: &[&str]) {
+    for key in keys {
+        if let Some(value) = from.get(key) {
+            to.insert((*key).to_owned(), value.clone());
+        }
+    }
+}
+
+/// Copies vendor extensions
+fn extensions(from: &Value, to: &mut Map<String, Value>) {
+    for (key, value) in from.as_object().into_iter().flatten() {
+        if key.starts_with("x-") && key != "x-nullable" {
+            to.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::path::Path;
+    use super::*;
+
+    fn fixture(name: &str) -> Spec {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples").join(name);
+        Spec {
+            name: Cow::Owned(path.file_name().unwrap().to_string_lossy().into_owned()),
+            content: Bytes::from(std::fs::read(path).unwrap()),
+        }
+    }
+
+    #[test]
+    fn converts_fixture() {
+        let converted = fixture("convert/swagger.yaml").to_openapi3().unwrap();
+        assert_eq!(converted.name, "swagger.yaml");
+        assert!(converted.is_yaml());
+        let expected = fixture("convert/openapi.yaml").parse().unwrap();
+        let converted = converted.parse().unwrap();
+        for (key, value) in expected.as_object().unwrap() {
+            assert_eq!(&converted[key], value, "{}", key);
+        }
+        assert_eq!(converted, expected);
+    }
+
+    #[test]
+    fn converts_petstore() {
+        let converted = fixture("openapi.json").to_openapi3().unwrap();
+        assert!(!converted.is_yaml());
+        let converted = converted.parse().unwrap();
+        assert_eq!(converted["servers"], json!([{ "url": "https://petstore.swagger.io/v2" }, { "url": "http://petstore.swagger.io/v2" }]));
+        assert!(converted.get("definitions").is_none());
+        assert!(!converted.to_string().contains("#/definitions/"));
+
+        let add_pet = &converted["paths"]["/pet"]["post"];
+        assert_eq!(add_pet["requestBody"]["content"]["application/xml"]["schema"]["$ref"], "#/components/schemas/Pet");
+        assert_eq!(add_pet["parameters"], Value::Null);
+        let upload = &converted["paths"]["/pet/{petId}/uploadImage"]["post"];
+        assert_eq!(upload["requestBody"]["content"]["multipart/form-data"]["schema"]["properties"]["file"]["format"], "binary");
+        let login = &converted["paths"]["/user/login"]["get"]["responses"]["200"];
+        assert_eq!(login["headers"]["X-Rate-Limit"]["schema"]["type"], "integer");
+        assert_eq!(converted["components"]["securitySchemes"]["petstore_auth"]["flows"]["implicit"]["scopes"]["read:pets"], "read your pets");
+    }
+
+    #[test]
+    fn keeps_openapi3() {
+        let spec = Spec { name: Cow::Borrowed("openapi.json"), content: Bytes::from_static(br#"{"openapi":"3.1.0"}"#) };
+        assert_eq!(spec.to_openapi3().unwrap().content, spec.content);
+
+        let spec = Spec { name: Cow::Borrowed("openapi.json"), content: Bytes::from_static(br#"{"info":{}}"#) };
+        assert!(matches!(spec.to_openapi3(), Err(ConvertError::UnknownVersion)));
+    }
+
+    #[test]
+    fn builds_servers_without_schemes_or_host() {
+        assert_eq!(servers(&json!({ "host": "example.com", "basePath": "/api" })), json!([{ "url": "//example.com/api" }]));
+        assert_eq!(servers(&json!({ "basePath": "/api" })), json!([{ "url": "/api" }]));
+        assert_eq!(servers(&json!({})), json!([{ "url": "/" }]));
+    }
+}
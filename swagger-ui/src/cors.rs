@@ -0,0 +1,141 @@
+use std::borrow::Cow;
+
+/// CORS policy of the spec and `swagger-ui-config.json` of a swagger-ui mount,
+/// letting other origins fetch them regardless of the CORS middleware of the host application.
+#[derive(Debug, Clone, Default)]
+pub struct Cors {
+    /// Origins allowed to fetch the files, e.g. `https://portal.example.com`, or `*` for any origin.
+    pub allowed_origins: Vec<Cow<'static, str>>,
+    /// Sends `Access-Control-Allow-Credentials: true` to listed origins, so they can fetch the files with cookies.
+    /// Other origins allowed by `*` still get `*`, without credentials.
+    /// Default: `false`.
+    pub allow_credentials: bool,
+    /// How long browsers may cache preflight responses, in seconds.
+    /// Default: `None`.
+    pub max_age: Option<u32>,
+}
+
+/// Methods the files can be fetched with
+const ALLOWED_METHODS: &str = "GET, HEAD, OPTIONS";
+
+impl Cors {
+    /// Allows any origin, without credentials
+    pub fn any() -> Self {
+        Self::origins(["*"])
+    }
+
+    /// Allows the specified origins, without credentials
+    pub fn origins<O: Into<Cow<'static, str>>>(origins: impl IntoIterator<Item = O>) -> Self {
+        Self {
+            allowed_origins: origins.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns `Access-Control-Allow-Origin` for a request from `origin`, if it's allowed.
+    /// Only listed origins are echoed: echoing any origin with credentials would let any site
+    /// read the files with the user's cookies.
+    fn allow_origin(&self, origin: Option<&str>) -> Option<String> {
+        let any = self.allowed_origins.iter().any(|o| o == "*");
+        match origin {
+            _ if any && !self.allow_credentials => Some("*".to_owned()),
+            Some(origin) if self.allowed_origins.iter().any(|o| o != "*" && same_origin(o, origin)) => Some(origin.to_owned()),
+            _ if any => Some("*".to_owned()),
+            _ => None,
+        }
+    }
+
+    /// Whether responses depend on the `Origin` request header, which caches must know about
+    fn varies(&self) -> bool {
+        self.allow_credentials || !self.allowed_origins.iter().any(|o| o == "*")
+    }
+
+    /// Headers for a response to a request from `origin`, i.e. the value of its `Origin` header
+    pub fn headers(&self, origin: Option<&str>) -> Vec<(&'static str, String)> {
+        let mut headers = vec![];
+        if let Some(allow_origin) = self.allow_origin(origin) {
+            let credentials = self.allow_credentials && allow_origin != "*";
+            headers.push(("Access-Control-Allow-Origin", allow_origin));
+            if credentials {
+                headers.push(("Access-Control-Allow-Credentials", "true".to_owned()));
+            }
+        }
+        if self.varies() {
+            headers.push(("Vary", "Origin".to_owned()));
+        }
+        headers
+    }
+
+    /// Headers for a response to a preflight `OPTIONS` request from `origin`,
+    /// allowing the headers listed in its `Access-Control-Request-Headers`
+    pub fn preflight_headers(&self, origin: Option<&str>, request_headers: Option<&str>) -> Vec<(&'static str, String)> {
+        let mut headers = self.headers(origin);
+        if self.allow_origin(origin).is_none() {
+            return headers;
+        }
+        headers.push(("Access-Control-Allow-Methods", ALLOWED_METHODS.to_owned()));
+        if let Some(request_headers) = request_headers.filter(|h| !h.trim().is_empty()) {
+            headers.push(("Access-Control-Allow-Headers", request_headers.to_owned()));
+        }
+        if let Some(max_age) = self.max_age {
+            headers.push(("Access-Control-Max-Age", max_age.to_string()));
+        }
+        headers
+    }
+}
+
+/// Origins are compared ignoring case of scheme and host, and a trailing slash
+fn same_origin(allowed: &str, origin: &str) -> bool {
+    allowed.trim_end_matches('/').eq_ignore_ascii_case(origin.trim_end_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cors;
+
+    fn header<'a>(headers: &'a [(&'static str, String)], name: &str) -> Option<&'a str> {
+        headers.iter().find(|(n, _)| *n == name).map(|(_, v)| v.as_str())
+    }
+
+    #[test]
+    fn any_origin() {
+        let headers = Cors::any().headers(Some("https://portal.example.com"));
+        assert_eq!(header(&headers, "Access-Control-Allow-Origin"), Some("*"));
+        assert_eq!(header(&headers, "Vary"), None);
+
+        // Any origin never gets credentials, only listed ones
+        let cors = Cors { allow_credentials: true, ..Cors::origins(["*", "https://portal.example.com"]) };
+        let headers = cors.headers(Some("https://evil.example.com"));
+        assert_eq!(header(&headers, "Access-Control-Allow-Origin"), Some("*"));
+        assert_eq!(header(&headers, "Access-Control-Allow-Credentials"), None);
+        assert_eq!(header(&headers, "Vary"), Some("Origin"));
+
+        let headers = cors.headers(Some("https://portal.example.com"));
+        assert_eq!(header(&headers, "Access-Control-Allow-Origin"), Some("https://portal.example.com"));
+        assert_eq!(header(&headers, "Access-Control-Allow-Credentials"), Some("true"));
+    }
+
+    #[test]
+    fn listed_origins() {
+        let cors = Cors::origins(["https://portal.example.com"]);
+        let headers = cors.headers(Some("https://Portal.example.com"));
+        assert_eq!(header(&headers, "Access-Control-Allow-Origin"), Some("https://Portal.example.com"));
+
+        for origin in [Some("https://evil.example.com"), None] {
+            let headers = cors.headers(origin);
+            assert_eq!(headers, [("Vary", "Origin".to_owned())]);
+        }
+    }
+
+    #[test]
+    fn preflight() {
+        let cors = Cors { max_age: Some(600), ..Cors::origins(["https://portal.example.com"]) };
+        let headers = cors.preflight_headers(Some("https://portal.example.com"), Some("authorization"));
+        assert_eq!(header(&headers, "Access-Control-Allow-Methods"), Some("GET, HEAD, OPTIONS"));
+        assert_eq!(header(&headers, "Access-Control-Allow-Headers"), Some("authorization"));
+        assert_eq!(header(&headers, "Access-Control-Max-Age"), Some("600"));
+
+        let headers = cors.preflight_headers(Some("https://evil.example.com"), Some("authorization"));
+        assert_eq!(header(&headers, "Access-Control-Allow-Methods"), None);
+    }
+}
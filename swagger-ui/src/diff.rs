@@ -0,0 +1,702 @@
+//! Comparison of two versions of a spec, classifying changes as breaking or not for clients of the API.
+//!
+//! Operations are matched by method and path, ignoring the names of path parameters.
+//! Schemas are compared depending on whether clients send them (parameters and request bodies)
+//! or receive them (responses): e.g. a new `enum` value is harmless in a request, but breaks clients
+//! reading it in a response. Changes to a schema referenced with `$ref` are reported once
+//! for the referenced schema rather than for each operation using it.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+use serde_json::Value;
+use crate::{ParseError, Spec};
+
+const METHODS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// How many `$ref`s are followed in a row, which also stops reference cycles
+const MAX_REFS: usize = 8;
+
+/// How deep schemas are compared
+const MAX_DEPTH: usize = 64;
+
+/// Changes between two versions of a spec
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diff {
+    /// Every change found, by path and method, then reusable schemas
+    pub changes: Vec<Change>,
+}
+
+/// A single change
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// Where the change is, e.g. `GET /pets query parameter limit`, or `schema #/definitions/Pet in requests`
+    pub location: String,
+    /// Path to the changed value within the schema of the location, e.g. `/tags/0/name`, empty for the location itself
+    pub pointer: String,
+    /// What changed
+    pub kind: ChangeKind,
+    /// Whether existing clients may break
+    pub breaking: bool,
+}
+
+/// Kind of a `Change`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+    /// Operation was added
+    OperationAdded,
+    /// Operation was removed
+    OperationRemoved,
+    /// Operation was marked `deprecated`
+    OperationDeprecated,
+    /// Parameter was added
+    ParameterAdded { required: bool },
+    /// Parameter was removed
+    ParameterRemoved,
+    /// Request body was added
+    BodyAdded { required: bool },
+    /// Request body was removed
+    BodyRemoved,
+    /// Parameter, request body or property became required
+    BecameRequired,
+    /// Parameter, request body or property became optional
+    BecameOptional,
+    /// Media type was added to a request body or response
+    MediaTypeAdded(String),
+    /// Media type was removed from a request body or response
+    MediaTypeRemoved(String),
+    /// Response status was added
+    ResponseAdded(String),
+    /// Response status was removed
+    ResponseRemoved(String),
+    /// Schema `type` changed
+    TypeChanged { old: String, new: String },
+    /// Property was added to an object schema
+    PropertyAdded { name: String, required: bool },
+    /// Property was removed from an object schema
+    PropertyRemoved(String),
+    /// Value was added to an `enum`
+    EnumValueAdded(Value),
+    /// Value was removed from an `enum`
+    EnumValueRemoved(Value),
+    /// A limit like `maxLength` or `enum` was added or made stricter
+    LimitTightened(&'static str),
+    /// A limit like `maxLength` or `enum` was removed or made looser
+    LimitRelaxed(&'static str),
+    /// The schemas of `allOf`, `anyOf` or `oneOf` were added or removed
+    CompositionChanged(&'static str),
+    /// Reusable schema was added
+    SchemaAdded(String),
+    /// Reusable schema was removed
+    SchemaRemoved(String),
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let required = |required: &bool| if *required { "required" } else { "optional" };
+        match self {
+            ChangeKind::OperationAdded => write!(f, "operation added"),
+            ChangeKind::OperationRemoved => write!(f, "operation removed"),
+            ChangeKind::OperationDeprecated => write!(f, "operation deprecated"),
+            ChangeKind::ParameterAdded { required: r } => write!(f, "{} parameter added", required(r)),
+            ChangeKind::ParameterRemoved => write!(f, "parameter removed"),
+            ChangeKind::BodyAdded { required: r } => write!(f, "{} request body added", required(r)),
+            ChangeKind::BodyRemoved => write!(f, "request body removed"),
+            ChangeKind::BecameRequired => write!(f, "became required"),
+            ChangeKind::BecameOptional => write!(f, "became optional"),
+            ChangeKind::MediaTypeAdded(media) => write!(f, "media type {} added", media),
+            ChangeKind::MediaTypeRemoved(media) => write!(f, "media type {} removed", media),
+            ChangeKind::ResponseAdded(status) => write!(f, "response {} added", status),
+            ChangeKind::ResponseRemoved(status) => write!(f, "response {} removed", status),
+            ChangeKind::TypeChanged { old, new } => write!(f, "type changed from {} to {}", old, new),
+            ChangeKind::PropertyAdded { name, required: r } => write!(f, "{} property `{}` added", required(r), name),
+            ChangeKind::PropertyRemoved(name) => write!(f, "property `{}` removed", name),
+            ChangeKind::EnumValueAdded(value) => write!(f, "enum value {} added", value),
+            ChangeKind::EnumValueRemoved(value) => write!(f, "enum value {} removed", value),
+            ChangeKind::LimitTightened(keyword) => write!(f, "`{}` tightened", keyword),
+            ChangeKind::LimitRelaxed(keyword) => write!(f, "`{}` relaxed", keyword),
+            ChangeKind::CompositionChanged(keyword) => write!(f, "`{}` schemas changed", keyword),
+            ChangeKind::SchemaAdded(name) => write!(f, "schema `{}` added", name),
+            ChangeKind::SchemaRemoved(name) => write!(f, "schema `{}` removed", name),
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = if self.breaking { "breaking" } else { "non-breaking" };
+        if self.pointer.is_empty() {
+            write!(f, "{}: {}: {}", severity, self.location, self.kind)
+        } else {
+            write!(f, "{}: {} at {}: {}", severity, self.location, self.pointer, self.kind)
+        }
+    }
+}
+
+impl Diff {
+    /// Whether any change may break existing clients
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(|change| change.breaking)
+    }
+
+    /// Changes which may break existing clients
+    pub fn breaking(&self) -> impl Iterator<Item = &Change> {
+        self.changes.iter().filter(|change| change.breaking)
+    }
+}
+
+impl Spec {
+    /// Compares the spec with a newer version, see [`diff`](crate::diff::diff)
+    pub fn diff(&self, new: &Spec) -> Result<Diff, ParseError> {
+        diff(self, new)
+    }
+}
+
+/// Parses and compares the `old` and `new` versions of a spec
+pub fn diff(old: &Spec, new: &Spec) -> Result<Diff, ParseError> {
+    Ok(diff_values(&old.parse()?, &new.parse()?))
+}
+
+/// Compares already parsed documents
+pub fn diff_values(old: &Value, new: &Value) -> Diff {
+    let mut differ = Differ { old, new, changes: vec![], compared: HashSet::new() };
+    differ.paths();
+    differ.reusable_schemas();
+    Diff { changes: differ.changes }
+}
+
+/// Whether a schema is sent or received by clients, which decides whether a change breaks them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Direction {
+    Request,
+    Response,
+}
+
+/// Normalized request body or response: whether it's required and the schema of each media type
+struct Content<'a> {
+    required: bool,
+    media: BTreeMap<String, Option<&'a Value>>,
+}
+
+struct Differ<'a> {
+    old: &'a Value,
+    new: &'a Value,
+    changes: Vec<Change>,
+    /// Schemas already compared when both sides are references, by old and new reference and direction,
+    /// which stops recursive schemas even when renamed. An inline side is finite, so it isn't tracked:
+    /// each inline schema compared with a shared one reports its own changes.
+    compared: HashSet<(String, String, Direction)>,
+}
+
+impl<'a> Differ<'a> {
+    fn push(&mut self, location: &str, pointer: &str, kind: ChangeKind, breaking: bool) {
+        self.changes.push(Change { location: location.to_owned(), pointer: pointer.to_owned(), kind, breaking });
+    }
+
+    fn paths(&mut self) {
+        let old_paths = paths(self.old);
+        let new_paths = paths(self.new);
+        let keys = old_paths.keys().chain(new_paths.keys()).cloned().collect::<BTreeSet<_>>();
+        for key in keys {
+            let (old_item, new_item) = (old_paths.get(&key), new_paths.get(&key));
+            for method in METHODS {
+                let old_op = old_item.and_then(|(_, item)| item.get(method));
+                let new_op = new_item.and_then(|(_, item)| item.get(method));
+                let template = new_item.or(old_item).map(|(template, _)| *template).unwrap_or_default();
+                let location = format!("{} {}", method.to_uppercase(), template);
+                match (old_op, new_op) {
+                    (None, Some(_)) => self.push(&location, "", ChangeKind::OperationAdded, false),
+                    (Some(_), None) => self.push(&location, "", ChangeKind::OperationRemoved, true),
+                    (Some(old_op), Some(new_op)) => {
+                        let (old_item, new_item) = (old_item.unwrap(), new_item.unwrap());
+                        self.operation(&location, (old_item.0, old_item.1, old_op), (new_item.0, new_item.1, new_op));
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+    }
+
+    /// Compares operations, given with their path template and path item
+    fn operation(&mut self, location: &str, old: (&str, &'a Value, &'a Value), new: (&str, &'a Value, &'a Value)) {
+        let deprecated = |operation: &Value| operation.get("deprecated").and_then(Value::as_bool).unwrap_or(false);
+        if !deprecated(old.2) && deprecated(new.2) {
+            self.push(location, "", ChangeKind::OperationDeprecated, false);
+        }
+
+        let old_params = parameters(self.old, old.0, old.1, old.2);
+        let new_params = parameters(self.new, new.0, new.1, new.2);
+        let keys = old_params.keys().chain(new_params.keys()).cloned().collect::<BTreeSet<_>>();
+        for key in keys {
+            let (old_param, new_param) = (old_params.get(&key).copied(), new_params.get(&key).copied());
+            let name = new_param.or(old_param).and_then(|param| param.get("name")).and_then(Value::as_str).unwrap_or_default();
+            let param_location = format!("{} {} parameter {}", location, key.0, name);
+            let required = |param: &Value| param.get("required").and_then(Value::as_bool).unwrap_or(false);
+            match (old_param, new_param) {
+                (None, Some(param)) => {
+                    let required = required(param);
+                    self.push(&param_location, "", ChangeKind::ParameterAdded { required }, required);
+                }
+                (Some(_), None) => self.push(&param_location, "", ChangeKind::ParameterRemoved, true),
+                (Some(old_param), Some(new_param)) => {
+                    self.required(&param_location, "", required(old_param), required(new_param), Direction::Request);
+                    // Swagger 2.0 parameters have their schema inline
+                    let old_schema = old_param.get("schema").unwrap_or(old_param);
+                    let new_schema = new_param.get("schema").unwrap_or(new_param);
+                    self.schema(&param_location, "", old_schema, new_schema, Direction::Request, 0);
+                }
+                (None, None) => {}
+            }
+        }
+
+        let body_location = format!("{} request body", location);
+        match (request_body(self.old, old.2), request_body(self.new, new.2)) {
+            (None, Some(body)) => self.push(&body_location, "", ChangeKind::BodyAdded { required: body.required }, body.required),
+            (Some(_), None) => self.push(&body_location, "", ChangeKind::BodyRemoved, true),
+            (Some(old_body), Some(new_body)) => {
+                self.required(&body_location, "", old_body.required, new_body.required, Direction::Request);
+                self.content(&body_location, old_body, new_body, Direction::Request);
+            }
+            (None, None) => {}
+        }
+
+        let old_responses = responses(self.old, old.2);
+        let new_responses = responses(self.new, new.2);
+        let statuses = old_responses.keys().chain(new_responses.keys()).cloned().collect::<BTreeSet<_>>();
+        for status in statuses {
+            let response_location = format!("{} response {}", location, status);
+            match (old_responses.get(&status), new_responses.get(&status)) {
+                (None, Some(_)) => self.push(&response_location, "", ChangeKind::ResponseAdded(status.clone()), false),
+                (Some(_), None) => self.push(&response_location, "", ChangeKind::ResponseRemoved(status.clone()), true),
+                (Some(old_response), Some(new_response)) => {
+                    let old_content = content(self.old, old_response, old.2, "produces");
+                    let new_content = content(self.new, new_response, new.2, "produces");
+                    self.content(&response_location, old_content, new_content, Direction::Response);
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
+    fn content(&mut self, location: &str, old: Content<'a>, new: Content<'a>, direction: Direction) {
+        let media_types = old.media.keys().chain(new.media.keys()).cloned().collect::<BTreeSet<_>>();
+        for media in media_types {
+            match (old.media.get(&media), new.media.get(&media)) {
+                // Sending a new media type is opt-in, receiving one is not
+                (None, Some(_)) => self.push(location, "", ChangeKind::MediaTypeAdded(media), direction == Direction::Response),
+                (Some(_), None) => self.push(location, "", ChangeKind::MediaTypeRemoved(media), true),
+                (Some(Some(old_schema)), Some(Some(new_schema))) => {
+                    let location = format!("{} {}", location, media);
+                    self.schema(&location, "", old_schema, new_schema, direction, 0);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Records a change of `required`, which breaks senders when added and receivers when removed
+    fn required(&mut self, location: &str, pointer: &str, old: bool, new: bool, direction: Direction) {
+        match (old, new) {
+            (false, true) => self.push(location, pointer, ChangeKind::BecameRequired, direction == Direction::Request),
+            (true, false) => self.push(location, pointer, ChangeKind::BecameOptional, direction == Direction::Response),
+            _ => {}
+        }
+    }
+
+    /// Records a restriction of the accepted values, which breaks senders, or a relaxation, which breaks receivers
+    fn restrict(&mut self, location: &str, pointer: &str, kind: ChangeKind, tightened: bool, direction: Direction) {
+        self.push(location, pointer, kind, tightened == (direction == Direction::Request));
+    }
+
+    fn schema(&mut self, location: &str, pointer: &str, old: &'a Value, new: &'a Value, direction: Direction, depth: usize) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        let (old_ref, old) = deref(self.old, old);
+        let (new_ref, new) = deref(self.new, new);
+        if let (Some(old_ref), Some(new_ref)) = (&old_ref, &new_ref) {
+            if !self.compared.insert((old_ref.clone(), new_ref.clone(), direction)) {
+                return;
+            }
+            // Referenced schemas are reported once, at their own location
+            if old_ref == new_ref {
+                let location = format!("schema {} in {}", new_ref, match direction {
+                    Direction::Request => "requests",
+                    Direction::Response => "responses",
+                });
+                return self.schema_value(&location, "", old, new, direction, depth);
+            }
+        }
+        self.schema_value(location, pointer, old, new, direction, depth)
+    }
+
+    fn schema_value(&mut self, location: &str, pointer: &str, old: &'a Value, new: &'a Value, direction: Direction, depth: usize) {
+        let (old_types, new_types) = (types(old), types(new));
+        if !old_types.is_empty() && !new_types.is_empty() && old_types != new_types {
+            let widened = old_types.is_subset(&new_types);
+            let narrowed = new_types.is_subset(&old_types);
+            let kind = ChangeKind::TypeChanged { old: join(&old_types), new: join(&new_types) };
+            let breaking = match direction {
+                Direction::Request => !widened,
+                Direction::Response => !narrowed,
+            };
+            self.push(location, pointer, kind, breaking);
+            if !widened && !narrowed {
+                return;
+            }
+        }
+
+        match (old.get("enum").and_then(Value::as_array), new.get("enum").and_then(Value::as_array)) {
+            (Some(old_values), Some(new_values)) => {
+                for value in old_values.iter().filter(|value| !new_values.contains(value)) {
+                    self.restrict(location, pointer, ChangeKind::EnumValueRemoved(value.clone()), true, direction);
+                }
+                for value in new_values.iter().filter(|value| !old_values.contains(value)) {
+                    self.restrict(location, pointer, ChangeKind::EnumValueAdded(value.clone()), false, direction);
+                }
+            }
+            (None, Some(_)) => self.restrict(location, pointer, ChangeKind::LimitTightened("enum"), true, direction),
+            (Some(_), None) => self.restrict(location, pointer, ChangeKind::LimitRelaxed("enum"), false, direction),
+            (None, None) => {}
+        }
+
+        for (keyword, lower) in [("minimum", true), ("exclusiveMinimum", true), ("minLength", true), ("minItems", true),
+                                 ("maximum", false), ("exclusiveMaximum", false), ("maxLength", false), ("maxItems", false)] {
+            let tightened = match (old.get(keyword).and_then(Value::as_f64), new.get(keyword).and_then(Value::as_f64)) {
+                (None, Some(_)) => true,
+                (Some(_), None) => false,
+                (Some(old), Some(new)) if old != new => (new > old) == lower,
+                _ => continue,
+            };
+            let kind = if tightened { ChangeKind::LimitTightened(keyword) } else { ChangeKind::LimitRelaxed(keyword) };
+            self.restrict(location, pointer, kind, tightened, direction);
+        }
+
+        let old_properties = old.get("properties").and_then(Value::as_object);
+        let new_properties = new.get("properties").and_then(Value::as_object);
+        let (old_required, new_required) = (required(old), required(new));
+        for (name, new_property) in new_properties.into_iter().flatten() {
+            let property_pointer = format!("{}/{}", pointer, name.replace('~', "~0").replace('/', "~1"));
+            match old_properties.and_then(|properties| properties.get(name)) {
+                Some(old_property) => {
+                    self.required(location, &property_pointer, old_required.contains(name.as_str()), new_required.contains(name.as_str()), direction);
+                    self.schema(location, &property_pointer, old_property, new_property, direction, depth + 1);
+                }
+                None => {
+                    let required = new_required.contains(name.as_str());
+                    self.push(location, pointer, ChangeKind::PropertyAdded { name: name.clone(), required }, required && direction == Direction::Request);
+                }
+            }
+        }
+        let removed = old_properties.into_iter().flatten()
+            .map(|(name, _)| name)
+            .filter(|name| !new_properties.is_some_and(|properties| properties.contains_key(*name)));
+        for name in removed {
+            self.push(location, pointer, ChangeKind::PropertyRemoved(name.clone()), direction == Direction::Response);
+        }
+
+        if let (Some(old_items), Some(new_items)) = (old.get("items"), new.get("items")) {
+            self.schema(location, &format!("{}/items", pointer), old_items, new_items, direction, depth + 1);
+        }
+
+        for keyword in ["allOf", "anyOf", "oneOf"] {
+            match (old.get(keyword).and_then(Value::as_array), new.get(keyword).and_then(Value::as_array)) {
+                (Some(old_schemas), Some(new_schemas)) if old_schemas.len() == new_schemas.len() => {
+                    for (i, (old_schema, new_schema)) in old_schemas.iter().zip(new_schemas).enumerate() {
+                        self.schema(location, &format!("{}/{}/{}", pointer, keyword, i), old_schema, new_schema, direction, depth + 1);
+                    }
+                }
+                (None, None) => {}
+                _ => self.push(location, pointer, ChangeKind::CompositionChanged(keyword), true),
+            }
+        }
+    }
+
+    fn reusable_schemas(&mut self) {
+        let (old, new) = (schemas(self.old), schemas(self.new));
+        for name in new.keys().filter(|name| !old.contains_key(*name)) {
+            self.push("schemas", "", ChangeKind::SchemaAdded(name.clone()), false);
+        }
+        // Removing a schema only breaks clients through the operations using it, which are reported
+        for name in old.keys().filter(|name| !new.contains_key(*name)) {
+            self.push("schemas", "", ChangeKind::SchemaRemoved(name.clone()), false);
+        }
+    }
+}
+
+/// Path items by template with parameter names erased, e.g. `/pets/{}`, with their original template
+fn paths(spec: &Value) -> BTreeMap<String, (&str, &Value)> {
+    let mut paths = BTreeMap::new();
+    for (template, item) in spec.get("paths").and_then(Value::as_object).into_iter().flatten() {
+        let key = template.split('/')
+            .map(|segment| if segment.starts_with('{') && segment.ends_with('}') { "{}" } else { segment })
+            .collect::<Vec<_>>()
+            .join("/");
+        paths.insert(key, (template.as_str(), deref(spec, item).1));
+    }
+    paths
+}
+
+/// Parameters of an operation by location and name, the operation ones overriding the path item ones.
+/// Path parameters are keyed by their position in `template`, so renamed ones still match.
+fn parameters<'a>(spec: &'a Value, template: &str, item: &'a Value, operation: &'a Value) -> BTreeMap<(String, String), &'a Value> {
+    let mut params = BTreeMap::new();
+    for declared in [item.get("parameters"), operation.get("parameters")] {
+        for param in declared.and_then(Value::as_array).into_iter().flatten() {
+            let param = deref(spec, param).1;
+            let location = param.get("in").and_then(Value::as_str).unwrap_or_default();
+            let name = param.get("name").and_then(Value::as_str).unwrap_or_default();
+            let key = match location {
+                "body" | "formData" => continue,
+                "path" => template.split('/')
+                    .filter(|segment| segment.starts_with('{'))
+                    .position(|segment| segment.trim_start_matches('{').trim_end_matches('}') == name)
+                    .map(|position| position.to_string())
+                    .unwrap_or_else(|| name.to_owned()),
+                _ => name.to_owned(),
+            };
+            params.insert((location.to_owned(), key), param);
+        }
+    }
+    params
+}
+
+/// Request body of an OpenAPI 3 operation, or body parameter of a Swagger 2.0 one
+fn request_body<'a>(spec: &'a Value, operation: &'a Value) -> Option<Content<'a>> {
+    if let Some(body) = operation.get("requestBody") {
+        return Some(content(spec, deref(spec, body).1, operation, "consumes"));
+    }
+    let param = operation.get("parameters").and_then(Value::as_array).into_iter().flatten()
+        .map(|param| deref(spec, param).1)
+        .find(|param| param.get("in").and_then(Value::as_str) == Some("body"))?;
+    let mut body = content(spec, param, operation, "consumes");
+    body.required = param.get("required").and_then(Value::as_bool).unwrap_or(false);
+    Some(body)
+}
+
+fn responses<'a>(spec: &'a Value, operation: &'a Value) -> BTreeMap<String, &'a Value> {
+    operation.get("responses").and_then(Value::as_object).into_iter().flatten()
+        .map(|(status, response)| (status.to_ascii_uppercase(), deref(spec, response).1))
+        .collect()
+}
+
+/// Media types and schemas of an OpenAPI 3 `content`, or of a Swagger 2.0 `schema` with the media types
+/// listed in `consumes` or `produces` of the operation or document
+fn content<'a>(spec: &'a Value, value: &'a Value, operation: &'a Value, media_types: &str) -> Content<'a> {
+    let required = value.get("required").and_then(Value::as_bool).unwrap_or(false);
+    if let Some(content) = value.get("content").and_then(Value::as_object) {
+        let media = content.iter()
+            .map(|(media, value)| (media.to_ascii_lowercase(), deref(spec, value).1.get("schema")))
+            .collect();
+        return Content { required, media };
+    }
+    let schema = match value.get("schema") {
+        Some(schema) => schema,
+        None => return Content { required, media: BTreeMap::new() },
+    };
+    let declared = operation.get(media_types).or_else(|| spec.get(media_types)).and_then(Value::as_array);
+    let media = match declared {
+        Some(declared) if !declared.is_empty() => declared.iter()
+            .filter_map(Value::as_str)
+            .map(|media| (media.to_ascii_lowercase(), Some(schema)))
+            .collect(),
+        _ => BTreeMap::from([("application/json".to_owned(), Some(schema))]),
+    };
+    Content { required, media }
+}
+
+/// Reusable schemas, from `components/schemas` or Swagger 2.0 `definitions`
+fn schemas(spec: &Value) -> serde_json::Map<String, Value> {
+    spec.pointer("/components/schemas").or_else(|| spec.get("definitions"))
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Follows local `$ref`s, returning the last reference followed
+fn deref<'a>(spec: &'a Value, mut value: &'a Value) -> (Option<String>, &'a Value) {
+    let mut reference = None;
+    for _ in 0..MAX_REFS {
+        match value.get("$ref").and_then(Value::as_str) {
+            Some(r) => match r.strip_prefix('#').and_then(|pointer| spec.pointer(pointer)) {
+                Some(target) => {
+                    reference = Some(r.to_owned());
+                    value = target;
+                }
+                None => break,
+            },
+            None => break,
+        }
+    }
+    (reference, value)
+}
+
+/// Allowed types of a schema, with `null` for OpenAPI 3.0 `nullable` ones
+fn types(schema: &Value) -> BTreeSet<&str> {
+    let mut types = match schema.get("type") {
+        Some(Value::String(t)) => BTreeSet::from([t.as_str()]),
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+        _ => BTreeSet::new(),
+    };
+    if !types.is_empty() && schema.get("nullable").and_then(Value::as_bool).unwrap_or(false) {
+        types.insert("null");
+    }
+    types
+}
+
+fn join(types: &BTreeSet<&str>) -> String {
+    types.iter().copied().collect::<Vec<_>>().join(" or ")
+}
+
+fn required(schema: &Value) -> BTreeSet<&str> {
+    schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use super::*;
+
+    fn spec(paths: Value, schemas: Value) -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "info": {"title": "pets", "version": "1"},
+            "paths": paths,
+            "components": {"schemas": schemas},
+        })
+    }
+
+    fn pets(limit: Value, pet: Value) -> Value {
+        spec(json!({
+            "/pets": {
+                "get": {
+                    "parameters": [limit],
+                    "responses": {"200": {"content": {"application/json": {"schema": {
+                        "type": "array", "items": {"$ref": "#/components/schemas/Pet"},
+                    }}}}},
+                },
+                "post": {
+                    "requestBody": {"content": {"application/json": {"schema": {"$ref": "#/components/schemas/Pet"}}}},
+                    "responses": {"201": {"description": "created"}},
+                },
+            },
+            "/pets/{id}": {
+                "delete": {
+                    "parameters": [{"name": "id", "in": "path", "required": true, "schema": {"type": "integer"}}],
+                    "responses": {"204": {"description": "deleted"}},
+                },
+            },
+        }), json!({"Pet": pet}))
+    }
+
+    fn changes(diff: &Diff) -> Vec<String> {
+        diff.changes.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn same_spec_has_no_changes() {
+        let spec = Spec {
+            name: "openapi.json".into(),
+            content: crate::Bytes::from(std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/examples/openapi.json")).unwrap()),
+        };
+        assert_eq!(spec.diff(&spec).unwrap(), Diff::default());
+    }
+
+    #[test]
+    fn classifies_operation_changes() {
+        let limit = json!({"name": "limit", "in": "query", "schema": {"type": "integer"}});
+        let pet = json!({"type": "object", "properties": {"name": {"type": "string"}}});
+        let old = pets(limit.clone(), pet.clone());
+        let mut new = pets(json!({"name": "limit", "in": "query", "required": true, "schema": {"type": "integer"}}), pet);
+        new["paths"]["/pets/{petId}"] = json!({"delete": {
+            "parameters": [{"name": "petId", "in": "path", "required": true, "schema": {"type": "integer"}}],
+            "responses": {"204": {"description": "deleted"}, "404": {"description": "not found"}},
+        }});
+        new["paths"].as_object_mut().unwrap().remove("/pets/{id}");
+        new["paths"]["/pets"].as_object_mut().unwrap().remove("post");
+        new["paths"]["/owners"] = json!({"get": {"responses": {"200": {"description": "owners"}}}});
+        new["paths"]["/pets"]["get"]["deprecated"] = json!(true);
+
+        let diff = diff_values(&old, &new);
+        assert_eq!(changes(&diff), [
+            "non-breaking: GET /owners: operation added",
+            "non-breaking: GET /pets: operation deprecated",
+            "breaking: GET /pets query parameter limit: became required",
+            "breaking: POST /pets: operation removed",
+            "non-breaking: DELETE /pets/{petId} response 404: response 404 added",
+        ]);
+        assert!(diff.is_breaking());
+        assert_eq!(diff.breaking().count(), 2);
+    }
+
+    #[test]
+    fn classifies_schema_changes_by_direction() {
+        let limit = json!({"name": "limit", "in": "query", "schema": {"type": "integer", "maximum": 100}});
+        let old = pets(limit, json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": {"type": "string"},
+                "status": {"type": "string", "enum": ["available", "sold"]},
+                "tag": {"type": "string"},
+            },
+        }));
+        let new = pets(json!({"name": "limit", "in": "query", "schema": {"type": "integer", "maximum": 50}}), json!({
+            "type": "object",
+            "required": ["name", "age"],
+            "properties": {
+                "name": {"type": "string"},
+                "status": {"type": "string", "enum": ["available", "sold", "lost"]},
+                "age": {"type": "integer"},
+            },
+        }));
+
+        assert_eq!(changes(&diff_values(&old, &new)), [
+            "breaking: GET /pets query parameter limit: `maximum` tightened",
+            "non-breaking: schema #/components/schemas/Pet in responses: required property `age` added",
+            "breaking: schema #/components/schemas/Pet in responses at /status: enum value \"lost\" added",
+            "breaking: schema #/components/schemas/Pet in responses: property `tag` removed",
+            "breaking: schema #/components/schemas/Pet in requests: required property `age` added",
+            "non-breaking: schema #/components/schemas/Pet in requests at /status: enum value \"lost\" added",
+            "non-breaking: schema #/components/schemas/Pet in requests: property `tag` removed",
+        ]);
+    }
+
+    #[test]
+    fn compares_renamed_recursive_schemas_once() {
+        let tree = |name: &str| {
+            let node = format!("#/components/schemas/{}", name);
+            spec(json!({"/tree": {"get": {"responses": {"200": {"content": {"application/json": {"schema": {"$ref": node}}}}}}}}),
+                 json!({name: {
+                     "type": "object",
+                     "properties": {"value": {"type": "string"}, "left": {"$ref": node}, "right": {"$ref": node}},
+                 }}))
+        };
+        let mut new = tree("TreeNode");
+        new["components"]["schemas"]["TreeNode"]["properties"]["value"] = json!({"type": "integer"});
+
+        assert_eq!(changes(&diff_values(&tree("Node"), &new)), [
+            "breaking: GET /tree response 200 application/json at /value: type changed from string to integer",
+            "non-breaking: schemas: schema `TreeNode` added",
+            "non-breaking: schemas: schema `Node` removed",
+        ]);
+    }
+
+    #[test]
+    fn compares_inline_schemas_extracted_into_a_component() {
+        let operation = |schema: Value| json!({"responses": {"200": {"content": {"application/json": {"schema": schema}}}}});
+        let old = spec(json!({
+            "/pets": {"get": operation(json!({"type": "object", "properties": {"id": {"type": "string"}}}))},
+            "/users": {"get": operation(json!({"type": "object", "properties": {"id": {"type": "boolean"}}}))},
+        }), json!({}));
+        let new = spec(json!({
+            "/pets": {"get": operation(json!({"$ref": "#/components/schemas/Id"}))},
+            "/users": {"get": operation(json!({"$ref": "#/components/schemas/Id"}))},
+        }), json!({"Id": {"type": "object", "properties": {"id": {"type": "integer"}}}}));
+
+        assert_eq!(changes(&diff_values(&old, &new)), [
+            "breaking: GET /pets response 200 application/json at /id: type changed from string to integer",
+            "breaking: GET /users response 200 application/json at /id: type changed from boolean to integer",
+            "non-breaking: schemas: schema `Id` added",
+        ]);
+    }
+}
@@ -0,0 +1,212 @@
+//! [Swagger Editor](https://github.com/swagger-api/swagger-editor) page, enabled with the `editor` feature.
+//!
+//! The editor is preloaded with the served `Spec`. In dev mode, created with [`Editor::saving_to`],
+//! the spec is read from its source file on each request and the page saves edits back to it
+//! with a `PUT` request to the spec url.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use rust_embed::RustEmbed;
+use crate::index::{inline_script, js_string};
+use crate::overlay::cow_to_bytes;
+use crate::renderer::Renderer;
+use crate::{Bytes, ParseError, Spec};
+
+/// Assets from swagger-editor-dist
+#[derive(RustEmbed)]
+#[folder = "$CARGO_MANIFEST_DIR/.editor"]
+pub struct EditorAssets;
+
+/// Swagger Editor renderer
+#[derive(Debug, Clone, Default)]
+pub struct Editor {
+    source: Option<PathBuf>,
+}
+
+/// Error returned by [`Editor::save`]
+#[derive(Debug)]
+pub enum SaveError {
+    /// Editor is not in dev mode
+    ReadOnly,
+    /// Edited spec is not valid JSON or YAML, it's not saved
+    Parse(ParseError),
+    /// Failed to write the source file
+    Io(std::io::Error),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::ReadOnly => write!(f, "editor is read-only"),
+            SaveError::Parse(error) => write!(f, "failed to parse spec: {}", error),
+            SaveError::Io(error) => write!(f, "failed to save spec: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl Editor {
+    /// Read-only editor, edits are lost on reload
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Dev mode editor, which reads the spec from `source` and saves edits back to it.
+    /// Don't use it in production: anyone reaching the editor mount can overwrite the file.
+    pub fn saving_to(source: impl Into<PathBuf>) -> Self {
+        Self {
+            source: Some(source.into()),
+        }
+    }
+
+    /// Source file of the spec in dev mode
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+
+    /// Current content of the spec: the source file in dev mode, `spec` content otherwise
+    pub fn spec_content(&self, spec: &Spec) -> std::io::Result<Bytes> {
+        match &self.source {
+            Some(source) => std::fs::read(source).map(Bytes::from),
+            None => Ok(spec.content.clone()),
+        }
+    }
+
+    /// Saves the edited spec to the source file, if it parses.
+    /// The spec is written to a temporary file next to the source, then renamed over it,
+    /// so the source is never left half written.
+    pub fn save(&self, content: &[u8]) -> Result<(), SaveError> {
+        let source = self.source.as_ref().ok_or(SaveError::ReadOnly)?;
+        let spec = Spec {
+            name: source.to_string_lossy().into_owned().into(),
+            content: Bytes::copy_from_slice(content),
+        };
+        spec.parse().map_err(SaveError::Parse)?;
+        replace_file(source, content).map_err(SaveError::Io)
+    }
+
+    fn index_html(&self, spec_url: &str) -> String {
+        let save = if self.source.is_some() {
+            format!(r#"
+      function save() {{
+        fetch({url}, {{ method: "PUT", body: window.editor.specSelectors.specStr() }})
+          .then(function(response) {{
+            if (!response.ok) return response.text().then(function(text) {{ throw new Error(text); }});
+            var button = document.getElementById("save");
+            button.textContent = "Saved";
+            setTimeout(function() {{ button.textContent = "Save"; }}, 2000);
+          }})
+          .catch(function(error) {{ alert("Failed to save: " + error.message); }});
+      }}
+      document.getElementById("save").addEventListener("click", save);
+      document.addEventListener("keydown", function(event) {{
+        if ((event.ctrlKey || event.metaKey) && event.key === "s") {{
+          event.preventDefault();
+          save();
+        }}
+      }});"#, url = js_string(spec_url))
+        } else {
+            String::new()
+        };
+        let button = if self.source.is_some() {
+            "\n    <button id=\"save\" style=\"position: fixed; top: 8px; right: 16px; z-index: 100;\">Save</button>"
+        } else {
+            ""
+        };
+
+        format!(r##"<!DOCTYPE html>
+<html lang="en">
+  <head>
+    <meta charset="UTF-8">
+    <title>Swagger Editor</title>
+    <link rel="stylesheet" type="text/css" href="./swagger-editor.css" />
+    <style>body {{ margin: 0; }} #swagger-editor {{ height: 100vh; }}</style>
+  </head>
+  <body>{button}
+    <div id="swagger-editor"></div>
+    <script src="./swagger-editor-bundle.js" charset="UTF-8"></script>
+    <script src="./swagger-editor-standalone-preset.js" charset="UTF-8"></script>
+    <script>
+      window.onload = function() {{
+        window.editor = SwaggerEditorBundle({{
+          url: {url},
+          dom_id: "#swagger-editor",
+          layout: "StandaloneLayout",
+          presets: [SwaggerEditorStandalonePreset]
+        }});
+      }};{save}
+    </script>
+  </body>
+</html>
+"##,
+            button = button,
+            url = js_string(spec_url),
+            save = inline_script(&save),
+        )
+    }
+}
+
+impl Renderer for Editor {
+    fn name(&self) -> &'static str {
+        "editor"
+    }
+
+    fn file(&self, path: &str, spec_url: &str) -> Option<Bytes> {
+        match path.trim_start_matches('/') {
+            "index.html" => Some(Bytes::from(self.index_html(spec_url))),
+            path => EditorAssets::get(path).map(cow_to_bytes),
+        }
+    }
+}
+
+/// Writes `content` to a temporary file in the directory of `path`, keeping its permissions, and renames it over `path`
+fn replace_file(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let file_name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let temp = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+    let result = std::fs::write(&temp, content)
+        .and_then(|()| match std::fs::metadata(path) {
+            Ok(metadata) => std::fs::set_permissions(&temp, metadata.permissions()),
+            Err(_) => Ok(()),
+        })
+        .and_then(|()| std::fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_only_editor() {
+        let editor = Editor::new();
+        let index = String::from_utf8(editor.file("index.html", "./openapi.json").unwrap().to_vec()).unwrap();
+        assert!(index.contains(r#"url: "./openapi.json""#));
+        assert!(!index.contains("PUT"));
+        assert!(editor.file("swagger-editor-bundle.js", "./openapi.json").is_some());
+        assert!(matches!(editor.save(b"{}"), Err(SaveError::ReadOnly)));
+    }
+
+    #[test]
+    fn dev_editor_saves() {
+        let source = std::env::temp_dir().join(format!("swagger-ui-editor-{}.yaml", std::process::id()));
+        std::fs::write(&source, "openapi: 3.0.0\n").unwrap();
+        let editor = Editor::saving_to(&source);
+        let spec = Spec { name: "openapi.yaml".into(), content: Bytes::from_static(b"stale") };
+
+        let index = String::from_utf8(editor.file("index.html", "./openapi.yaml").unwrap().to_vec()).unwrap();
+        assert!(index.contains(r#"fetch("./openapi.yaml", { method: "PUT""#));
+
+        assert!(matches!(editor.save(b"openapi: [3.0.0"), Err(SaveError::Parse(_))));
+        assert_eq!(editor.spec_content(&spec).unwrap(), "openapi: 3.0.0\n");
+
+        editor.save(b"openapi: 3.0.3\n").unwrap();
+        assert_eq!(editor.spec_content(&spec).unwrap(), "openapi: 3.0.3\n");
+        let temp = source.with_file_name(format!(".{}.{}.tmp", source.file_name().unwrap().to_string_lossy(), std::process::id()));
+        assert!(!temp.exists());
+        std::fs::remove_file(source).unwrap();
+    }
+}
@@ -0,0 +1,871 @@
+//! Validation of requests and responses against the spec, enabled with the `enforce` feature.
+//!
+//! The adapters wrap an `Enforcer` as middleware: a tower layer for axum, an actix-web middleware
+//! and a Rocket fairing. Only requests to operations documented in the spec are checked,
+//! others, like the ones to swagger-ui itself, pass through.
+//!
+//! Parameters and JSON bodies are checked against their schemas: `type`, `nullable`, `enum`, `const`,
+//! `required`, `properties`, `additionalProperties`, `items`, length, size and range limits,
+//! `allOf`, `anyOf` and `oneOf`. `format` and `pattern` are not checked.
+//!
+//! Bodies are buffered to be checked, up to `Enforcer::body_limit`. With `OnViolation::Reject`, larger requests
+//! are rejected with `413 Payload Too Large` and larger responses replaced with `500 Internal Server Error`.
+//! With `OnViolation::Log`, larger bodies and bodies of unknown size, like streamed responses,
+//! pass through unchanged without their content being checked.
+
+use std::fmt;
+use percent_encoding::percent_decode_str;
+use serde_json::{json, Number, Value};
+use crate::{Bytes, ParseError, Spec};
+use crate::operation::{find_path, resolve};
+
+/// How deep schemas are followed, which also stops recursive schemas without data
+const MAX_DEPTH: usize = 64;
+
+/// Default of `Enforcer::with_body_limit`, 1 MiB
+pub const DEFAULT_BODY_LIMIT: usize = 1024 * 1024;
+
+/// What is done with requests and responses not matching the spec
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnViolation {
+    /// Violations are logged as warnings with the `log` crate, requests and responses pass through
+    Log,
+    /// Requests are rejected with `400 Bad Request`, `413 Payload Too Large` or `415 Unsupported Media Type`,
+    /// and responses are replaced with `500 Internal Server Error`
+    Reject,
+}
+
+/// Validates requests and responses of the documented operations against the spec
+#[derive(Debug, Clone)]
+pub struct Enforcer {
+    spec: Value,
+    base_path: String,
+    on_violation: OnViolation,
+    body_limit: usize,
+}
+
+/// A documented operation, matched by a request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operation {
+    method: String,
+    template: String,
+    path_params: Vec<(String, String)>,
+}
+
+/// A request to a documented operation
+#[derive(Debug, Clone, Default)]
+pub struct Request<'a> {
+    /// Query string, without `?`
+    pub query: Option<&'a str>,
+    /// Request headers
+    pub headers: Vec<(&'a str, &'a str)>,
+    /// Request body, `None` if it isn't available to the middleware and can't be checked
+    pub body: Option<&'a [u8]>,
+}
+
+/// A response of a documented operation
+#[derive(Debug, Clone, Default)]
+pub struct Response<'a> {
+    /// Status code
+    pub status: u16,
+    /// Value of the `Content-Type` header
+    pub content_type: Option<&'a str>,
+    /// Response body
+    pub body: &'a [u8],
+}
+
+/// A response replacing a rejected request or response, with a JSON body listing the violations
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rejection {
+    /// Status code
+    pub status: u16,
+    /// JSON body
+    pub body: Bytes,
+}
+
+/// A mismatch between a request or response and the spec
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// Where the mismatch is
+    pub location: Location,
+    /// JSON pointer to the offending value within the location, empty for the whole value
+    pub pointer: String,
+    /// What is wrong with the value
+    pub kind: ViolationKind,
+}
+
+/// Location of a `Violation`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Location {
+    /// Path parameter with this name
+    Path(String),
+    /// Query parameter with this name
+    Query(String),
+    /// Request header with this name
+    Header(String),
+    /// Request body
+    RequestBody,
+    /// Response status
+    Status,
+    /// Response body
+    ResponseBody,
+}
+
+/// Kind of a `Violation`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViolationKind {
+    /// A required value is missing
+    Missing,
+    /// Value has a wrong type, `expected` lists the allowed ones
+    InvalidType { expected: String },
+    /// Value is not one of `enum`, or not `const`
+    NotAllowed,
+    /// Value is outside of a limit, e.g. `maxLength`
+    Limit { keyword: &'static str, limit: Value },
+    /// Property is not allowed by `additionalProperties: false`
+    UnexpectedProperty(String),
+    /// Value matches none of the `anyOf` schemas, or not exactly one of the `oneOf` ones
+    NoMatch(&'static str),
+    /// Content type is not documented
+    UnsupportedMediaType(Option<String>),
+    /// Body is not valid JSON
+    InvalidJson(String),
+    /// Response status is not documented and there is no `default` response
+    UndocumentedStatus(u16),
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Location::Path(name) => write!(f, "path parameter `{}`", name),
+            Location::Query(name) => write!(f, "query parameter `{}`", name),
+            Location::Header(name) => write!(f, "header `{}`", name),
+            Location::RequestBody => write!(f, "request body"),
+            Location::Status => write!(f, "response status"),
+            Location::ResponseBody => write!(f, "response body"),
+        }
+    }
+}
+
+impl fmt::Display for ViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ViolationKind::Missing => write!(f, "is required"),
+            ViolationKind::InvalidType { expected } => write!(f, "expected {}", expected),
+            ViolationKind::NotAllowed => write!(f, "is not an allowed value"),
+            ViolationKind::Limit { keyword, limit } => write!(f, "violates `{}: {}`", keyword, limit),
+            ViolationKind::UnexpectedProperty(name) => write!(f, "unexpected property `{}`", name),
+            ViolationKind::NoMatch(keyword) => write!(f, "does not match the `{}` schemas", keyword),
+            ViolationKind::UnsupportedMediaType(Some(content_type)) => write!(f, "undocumented content type {:?}", content_type),
+            ViolationKind::UnsupportedMediaType(None) => write!(f, "missing content type"),
+            ViolationKind::InvalidJson(err) => write!(f, "invalid JSON: {}", err),
+            ViolationKind::UndocumentedStatus(status) => write!(f, "{} is not documented", status),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.pointer.is_empty() {
+            write!(f, "{}: {}", self.location, self.kind)
+        } else {
+            write!(f, "{} at {}: {}", self.location, self.pointer, self.kind)
+        }
+    }
+}
+
+impl std::error::Error for Violation {}
+
+impl Operation {
+    /// Method of the operation, in lower case
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// Path template of the operation, e.g. `/pet/{petId}`
+    pub fn path(&self) -> &str {
+        &self.template
+    }
+}
+
+/// Whether values are sent or received, which exempts `readOnly` or `writeOnly` properties from `required`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Request,
+    Response,
+}
+
+impl Enforcer {
+    /// Create a new `Enforcer` for the API described by `spec`.
+    ///
+    /// Request paths are matched relative to the `basePath` of Swagger 2.0 specs,
+    /// or the path of the first server of OpenAPI 3 ones, see `with_base_path`.
+    pub fn new(spec: &Spec, on_violation: OnViolation) -> Result<Self, ParseError> {
+        let spec = spec.parse()?;
+        let base_path = match spec.get("basePath") {
+            Some(Value::String(base_path)) => base_path.clone(),
+            _ => spec.pointer("/servers/0/url").and_then(Value::as_str).map(server_path).unwrap_or_default(),
+        };
+        Ok(Self { spec, base_path: base_path.trim_end_matches('/').to_owned(), on_violation, body_limit: DEFAULT_BODY_LIMIT })
+    }
+
+    /// Matches request paths relative to `base_path` instead of the one of the spec
+    pub fn with_base_path(mut self, base_path: impl Into<String>) -> Self {
+        self.base_path = base_path.into().trim_end_matches('/').to_owned();
+        self
+    }
+
+    /// Buffers and checks bodies up to `limit` bytes instead of `DEFAULT_BODY_LIMIT`
+    pub fn with_body_limit(mut self, limit: usize) -> Self {
+        self.body_limit = limit;
+        self
+    }
+
+    /// What is done with mismatches
+    pub fn on_violation(&self) -> OnViolation {
+        self.on_violation
+    }
+
+    /// Size in bytes of the largest body the adapters buffer to check it
+    pub fn body_limit(&self) -> usize {
+        self.body_limit
+    }
+
+    /// Whether the adapters buffer a body of `size` bytes, `None` if unknown, to check it.
+    /// With `OnViolation::Reject` every body is buffered, up to `body_limit`, see `body_too_large`.
+    pub fn checks_body(&self, size: Option<u64>) -> bool {
+        self.on_violation == OnViolation::Reject || size.is_some_and(|size| size <= self.body_limit as u64)
+    }
+
+    /// Rejection of a body at `location` found larger than `body_limit` while buffering it:
+    /// `413 Payload Too Large` for requests, `500 Internal Server Error` for responses
+    pub fn body_too_large(&self, location: Location) -> Rejection {
+        let (status, message) = match location {
+            Location::ResponseBody => (500, "response does not match the spec"),
+            _ => (413, "request body is too large"),
+        };
+        let violation = format!("{} is larger than {} bytes", location, self.body_limit);
+        Rejection::with_messages(status, message, &[violation])
+    }
+
+    /// Logs that the body at `location` of a request to `operation` passes through unchecked,
+    /// because it is larger than `body_limit` or of unknown size. Only happens with `OnViolation::Log`.
+    pub fn skip_body(&self, operation: &Operation, location: Location) {
+        if self.on_violation == OnViolation::Log {
+            log::warn!("{} {} {} is not checked: larger than {} bytes or streamed",
+                       operation.method.to_uppercase(), operation.template, location, self.body_limit);
+        }
+    }
+
+    /// Returns the documented operation a `method` request to `path` is sent to,
+    /// `None` if there is none and the request should not be checked
+    pub fn operation(&self, method: &str, path: &str) -> Option<Operation> {
+        let path = path.strip_prefix(self.base_path.as_str())
+            .filter(|path| path.is_empty() || path.starts_with('/'))?;
+        let path = find_path(&self.spec, path)?;
+        let method = method.to_ascii_lowercase();
+        let method = if path.item.get(&method).is_some() {
+            method
+        } else if method == "head" && path.item.get("get").is_some() {
+            "get".to_owned()
+        } else {
+            return None;
+        };
+        let path_params = path.params.into_iter()
+            .map(|(name, value)| (name, percent_decode_str(&value).decode_utf8_lossy().into_owned()))
+            .collect();
+        Some(Operation { method, template: path.template.to_owned(), path_params })
+    }
+
+    /// Returns every mismatch between `request` and the documented parameters and body of `operation`
+    pub fn check_request(&self, operation: &Operation, request: &Request) -> Vec<Violation> {
+        let mut violations = vec![];
+        let op = match self.operation_value(operation) {
+            Some(op) => op,
+            None => return violations,
+        };
+        let query = url::form_urlencoded::parse(request.query.unwrap_or_default().as_bytes())
+            .into_owned()
+            .collect::<Vec<_>>();
+
+        let mut body_param = None;
+        for param in self.parameters(operation) {
+            let name = param.get("name").and_then(Value::as_str).unwrap_or_default();
+            let (location, values) = match param.get("in").and_then(Value::as_str) {
+                Some("path") => (Location::Path(name.to_owned()), values(&operation.path_params, name)),
+                Some("query") => (Location::Query(name.to_owned()), values(&query, name)),
+                Some("header") => (Location::Header(name.to_owned()), request.headers.iter()
+                    .filter(|(n, _)| n.eq_ignore_ascii_case(name))
+                    .map(|(_, value)| value.to_string())
+                    .collect()),
+                Some("body") => {
+                    body_param = Some(param);
+                    continue;
+                }
+                _ => continue,
+            };
+            if values.is_empty() {
+                if param.get("required").and_then(Value::as_bool).unwrap_or(false) {
+                    violations.push(Violation { location, pointer: String::new(), kind: ViolationKind::Missing });
+                }
+                continue;
+            }
+            // Swagger 2.0 parameters have their schema inline
+            let schema = self.resolve(param.get("schema").unwrap_or(param));
+            let value = self.coerce(schema, &values);
+            self.check_value(location, schema, &value, Direction::Request, &mut violations);
+        }
+
+        let content_type = request.headers.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            .map(|(_, value)| *value);
+        let (required, media) = match (op.get("requestBody"), body_param) {
+            (Some(body), _) => {
+                let body = self.resolve(body);
+                (body.get("required").and_then(Value::as_bool).unwrap_or(false), self.media(body, content_type))
+            }
+            (None, Some(param)) => {
+                let consumes = op.get("consumes").or_else(|| self.spec.get("consumes"));
+                let media = match consumes.and_then(Value::as_array) {
+                    Some(consumes) if !consumes.is_empty() => {
+                        let documented = consumes.iter().filter_map(Value::as_str);
+                        matching_media(documented, content_type).map(|_| param.get("schema"))
+                    }
+                    _ => Ok(param.get("schema")),
+                };
+                (param.get("required").and_then(Value::as_bool).unwrap_or(false), media)
+            }
+            (None, None) => return violations,
+        };
+        if let Some(body) = request.body {
+            self.check_body(Location::RequestBody, required, media, content_type, body, Direction::Request, &mut violations);
+        }
+        violations
+    }
+
+    /// Returns every mismatch between `response` and the documented responses of `operation`
+    pub fn check_response(&self, operation: &Operation, response: &Response) -> Vec<Violation> {
+        let mut violations = vec![];
+        let responses = match self.operation_value(operation).and_then(|op| op.get("responses")) {
+            Some(responses) => responses,
+            None => return violations,
+        };
+        let status = response.status.to_string();
+        let range = format!("{}XX", &status[..1]);
+        let documented = responses.get(&status)
+            .or_else(|| responses.get(&range))
+            .or_else(|| responses.get(range.to_ascii_lowercase()))
+            .or_else(|| responses.get("default"));
+        let documented = match documented {
+            Some(documented) => self.resolve(documented),
+            None => {
+                violations.push(Violation {
+                    location: Location::Status,
+                    pointer: String::new(),
+                    kind: ViolationKind::UndocumentedStatus(response.status),
+                });
+                return violations;
+            }
+        };
+        if response.body.is_empty() {
+            return violations;
+        }
+
+        let media = if self.spec.get("swagger").is_some() {
+            Ok(documented.get("schema"))
+        } else if documented.get("content").is_some() {
+            self.media(documented, response.content_type)
+        } else {
+            Ok(None)
+        };
+        self.check_body(Location::ResponseBody, false, media, response.content_type, response.body, Direction::Response, &mut violations);
+        violations
+    }
+
+    /// Checks `request`, logging mismatches or returning a `400`/`415` rejection depending on `on_violation`
+    pub fn enforce_request(&self, operation: &Operation, request: &Request) -> Result<(), Rejection> {
+        let violations = self.check_request(operation, request);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        match self.on_violation {
+            OnViolation::Log => {
+                for violation in &violations {
+                    log::warn!("{} {} request does not match the spec: {}", operation.method.to_uppercase(), operation.template, violation);
+                }
+                Ok(())
+            }
+            OnViolation::Reject => {
+                let unsupported = violations.iter().any(|v| matches!(v.kind, ViolationKind::UnsupportedMediaType(_)));
+                Err(Rejection::new(if unsupported { 415 } else { 400 }, "request does not match the spec", &violations))
+            }
+        }
+    }
+
+    /// Checks `response`, logging mismatches or returning a `500` rejection depending on `on_violation`.
+    /// Rejected responses are logged as errors, since their content is lost.
+    pub fn enforce_response(&self, operation: &Operation, response: &Response) -> Result<(), Rejection> {
+        let violations = self.check_response(operation, response);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        let level = match self.on_violation {
+            OnViolation::Log => log::Level::Warn,
+            OnViolation::Reject => log::Level::Error,
+        };
+        for violation in &violations {
+            log::log!(level, "{} {} response does not match the spec: {}", operation.method.to_uppercase(), operation.template, violation);
+        }
+        match self.on_violation {
+            OnViolation::Log => Ok(()),
+            OnViolation::Reject => Err(Rejection::new(500, "response does not match the spec", &violations)),
+        }
+    }
+
+    fn operation_value(&self, operation: &Operation) -> Option<&Value> {
+        let item = self.resolve(self.spec.get("paths")?.get(&operation.template)?);
+        item.get(&operation.method)
+    }
+
+    /// Parameters of the path item and of the operation, the latter overriding the former
+    fn parameters(&self, operation: &Operation) -> Vec<&Value> {
+        let item = match self.spec.get("paths").and_then(|paths| paths.get(&operation.template)) {
+            Some(item) => self.resolve(item),
+            None => return vec![],
+        };
+        let key = |param: &Value| (param.get("name").cloned(), param.get("in").cloned());
+
+        let mut params = self.declared(item.get(&operation.method).and_then(|op| op.get("parameters")));
+        let overridden = params.iter().map(|param| key(param)).collect::<Vec<_>>();
+        params.extend(self.declared(item.get("parameters")).into_iter().filter(|param| !overridden.contains(&key(param))));
+        params
+    }
+
+    fn declared<'a>(&'a self, parameters: Option<&'a Value>) -> Vec<&'a Value> {
+        parameters.and_then(Value::as_array)
+            .map(|params| params.iter().map(|param| self.resolve(param)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the schema documented for `content_type` in an OpenAPI 3 request body or response
+    fn media<'a>(&'a self, documented: &'a Value, content_type: Option<&str>) -> Result<Option<&'a Value>, ViolationKind> {
+        let content = match documented.get("content").and_then(Value::as_object) {
+            Some(content) if !content.is_empty() => content,
+            _ => return Ok(None),
+        };
+        let media = matching_media(content.keys().map(String::as_str), content_type)?;
+        Ok(content.get(media).and_then(|media| media.get("schema")))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn check_body(&self, location: Location, required: bool, media: Result<Option<&Value>, ViolationKind>,
+                  content_type: Option<&str>, body: &[u8], direction: Direction, violations: &mut Vec<Violation>) {
+        if body.is_empty() {
+            if required {
+                violations.push(Violation { location, pointer: String::new(), kind: ViolationKind::Missing });
+            }
+            return;
+        }
+        let schema = match media {
+            Ok(Some(schema)) => self.resolve(schema),
+            Ok(None) => return,
+            Err(kind) => {
+                violations.push(Violation { location, pointer: String::new(), kind });
+                return;
+            }
+        };
+        if !content_type.map(essence).unwrap_or("application/json").contains("json") {
+            return;
+        }
+        match serde_json::from_slice::<Value>(body) {
+            Ok(value) => self.check_value(location, schema, &value, direction, violations),
+            Err(err) => violations.push(Violation { location, pointer: String::new(), kind: ViolationKind::InvalidJson(err.to_string()) }),
+        }
+    }
+
+    fn check_value(&self, location: Location, schema: &Value, value: &Value, direction: Direction, violations: &mut Vec<Violation>) {
+        let mut errors = vec![];
+        self.check_schema(schema, value, String::new(), direction, 0, &mut errors);
+        violations.extend(errors.into_iter().map(|(pointer, kind)| Violation { location: location.clone(), pointer, kind }));
+    }
+
+    /// Collects every mismatch between `value` and `schema`, with the pointer to the offending value
+    fn check_schema(&self, schema: &Value, value: &Value, pointer: String, direction: Direction, depth: usize,
+                    errors: &mut Vec<(String, ViolationKind)>) {
+        let schema = self.resolve(schema);
+        if depth > MAX_DEPTH {
+            return;
+        }
+
+        let types = types(schema);
+        if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+            errors.push((pointer, ViolationKind::InvalidType { expected: types.join(" or ") }));
+            return;
+        }
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+            let nullable = value.is_null() && types.contains(&"null");
+            if !allowed.contains(value) && !nullable {
+                errors.push((pointer.clone(), ViolationKind::NotAllowed));
+            }
+        }
+        if schema.get("const").is_some_and(|constant| constant != value) {
+            errors.push((pointer.clone(), ViolationKind::NotAllowed));
+        }
+
+        for keyword in ["allOf", "anyOf", "oneOf"] {
+            let schemas = match schema.get(keyword).and_then(Value::as_array) {
+                Some(schemas) => schemas,
+                None => continue,
+            };
+            if keyword == "allOf" {
+                for schema in schemas {
+                    self.check_schema(schema, value, pointer.clone(), direction, depth + 1, errors);
+                }
+                continue;
+            }
+            let matching = schemas.iter()
+                .filter(|schema| {
+                    let mut errors = vec![];
+                    self.check_schema(schema, value, pointer.clone(), direction, depth + 1, &mut errors);
+                    errors.is_empty()
+                })
+                .count();
+            if matching == 0 || (keyword == "oneOf" && matching > 1) {
+                errors.push((pointer.clone(), ViolationKind::NoMatch(keyword)));
+            }
+        }
+
+        match value {
+            Value::String(s) => {
+                let length = s.chars().count() as f64;
+                limit(schema, "minLength", &pointer, errors, |min| length >= min);
+                limit(schema, "maxLength", &pointer, errors, |max| length <= max);
+            }
+            Value::Number(n) => {
+                let n = n.as_f64().unwrap_or_default();
+                let exclusive = |keyword| schema.get(keyword).and_then(Value::as_bool).unwrap_or(false);
+                if exclusive("exclusiveMinimum") {
+                    limit(schema, "minimum", &pointer, errors, |min| n > min);
+                } else {
+                    limit(schema, "minimum", &pointer, errors, |min| n >= min);
+                }
+                if exclusive("exclusiveMaximum") {
+                    limit(schema, "maximum", &pointer, errors, |max| n < max);
+                } else {
+                    limit(schema, "maximum", &pointer, errors, |max| n <= max);
+                }
+                // OpenAPI 3.1 exclusive limits are numbers
+                limit(schema, "exclusiveMinimum", &pointer, errors, |min| n > min);
+                limit(schema, "exclusiveMaximum", &pointer, errors, |max| n < max);
+            }
+            Value::Array(items) => {
+                let count = items.len() as f64;
+                limit(schema, "minItems", &pointer, errors, |min| count >= min);
+                limit(schema, "maxItems", &pointer, errors, |max| count <= max);
+                if schema.get("uniqueItems").and_then(Value::as_bool).unwrap_or(false)
+                    && items.iter().enumerate().any(|(i, item)| items[..i].contains(item)) {
+                    errors.push((pointer.clone(), ViolationKind::Limit { keyword: "uniqueItems", limit: json!(true) }));
+                }
+                if let Some(item_schema) = schema.get("items").filter(|items| items.is_object()) {
+                    for (i, item) in items.iter().enumerate() {
+                        self.check_schema(item_schema, item, format!("{}/{}", pointer, i), direction, depth + 1, errors);
+                    }
+                }
+            }
+            Value::Object(object) => {
+                let properties = schema.get("properties").and_then(Value::as_object);
+                for name in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+                    let exempt = properties.and_then(|properties| properties.get(name))
+                        .map(|property| self.resolve(property))
+                        .and_then(|property| property.get(match direction {
+                            Direction::Request => "readOnly",
+                            Direction::Response => "writeOnly",
+                        }))
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false);
+                    if !exempt && !object.contains_key(name) {
+                        errors.push((format!("{}/{}", pointer, escape(name)), ViolationKind::Missing));
+                    }
+                }
+                for (name, property) in object {
+                    let pointer = format!("{}/{}", pointer, escape(name));
+                    match (properties.and_then(|properties| properties.get(name)), schema.get("additionalProperties")) {
+                        (Some(property_schema), _) => self.check_schema(property_schema, property, pointer, direction, depth + 1, errors),
+                        (None, Some(Value::Bool(false))) => errors.push((pointer, ViolationKind::UnexpectedProperty(name.clone()))),
+                        (None, Some(additional)) if additional.is_object() => self.check_schema(additional, property, pointer, direction, depth + 1, errors),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Converts the raw values of a parameter to the JSON value its schema describes
+    fn coerce(&self, schema: &Value, values: &[String]) -> Value {
+        if types(schema).contains(&"array") {
+            let items = schema.get("items").map(|items| self.resolve(items)).unwrap_or(&Value::Null);
+            return Value::Array(values.iter()
+                .flat_map(|value| value.split(','))
+                .map(|value| coerce_scalar(items, value))
+                .collect());
+        }
+        coerce_scalar(schema, &values[0])
+    }
+
+    fn resolve<'a>(&'a self, value: &'a Value) -> &'a Value {
+        resolve(&self.spec, value)
+    }
+}
+
+impl Rejection {
+    fn new(status: u16, message: &str, violations: &[Violation]) -> Self {
+        Self::with_messages(status, message, &violations.iter().map(ToString::to_string).collect::<Vec<_>>())
+    }
+
+    fn with_messages(status: u16, message: &str, violations: &[String]) -> Self {
+        let body = json!({
+            "message": message,
+            "violations": violations,
+        });
+        Self { status, body: Bytes::from(body.to_string()) }
+    }
+}
+
+/// Path of a server url, which may be absolute or relative
+fn server_path(url: &str) -> String {
+    let path = match url.find("://") {
+        Some(scheme) => url[scheme + 3..].find('/').map(|path| &url[scheme + 3 + path..]).unwrap_or_default(),
+        None => url,
+    };
+    // Server variables can't be resolved
+    if path.contains('{') { String::new() } else { path.to_owned() }
+}
+
+/// Values of the parameter `name`
+fn values(pairs: &[(String, String)], name: &str) -> Vec<String> {
+    pairs.iter().filter(|(n, _)| n == name).map(|(_, value)| value.clone()).collect()
+}
+
+/// Media type without parameters, e.g. `application/json` for `application/json; charset=utf-8`
+fn essence(content_type: &str) -> &str {
+    content_type.split(';').next().unwrap_or_default().trim()
+}
+
+/// Finds the documented media type matching `content_type`, allowing `type/*` and `*/*` ranges
+fn matching_media<'a>(mut documented: impl Iterator<Item = &'a str>, content_type: Option<&str>) -> Result<&'a str, ViolationKind> {
+    let content_type = match content_type {
+        Some(content_type) => essence(content_type).to_ascii_lowercase(),
+        None => return Err(ViolationKind::UnsupportedMediaType(None)),
+    };
+    documented
+        .find(|media| {
+            let media = essence(media).to_ascii_lowercase();
+            media == content_type || media == "*/*"
+                || media.strip_suffix("/*").is_some_and(|kind| content_type.split('/').next() == Some(kind))
+        })
+        .ok_or(ViolationKind::UnsupportedMediaType(Some(content_type)))
+}
+
+/// Allowed types of a schema, with `null` for OpenAPI 3.0 `nullable` ones
+fn types(schema: &Value) -> Vec<&str> {
+    let mut types = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+    if !types.is_empty() && schema.get("nullable").and_then(Value::as_bool).unwrap_or(false) {
+        types.push("null");
+    }
+    types
+}
+
+fn has_type(value: &Value, t: &str) -> bool {
+    match t {
+        "integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        // e.g. `file` of Swagger 2.0 form parameters
+        _ => true,
+    }
+}
+
+fn coerce_scalar(schema: &Value, value: &str) -> Value {
+    let types = types(schema);
+    if types.contains(&"integer") {
+        if let Ok(n) = value.parse::<i64>() {
+            return json!(n);
+        }
+    }
+    if types.contains(&"number") {
+        if let Some(n) = value.parse::<f64>().ok().and_then(Number::from_f64) {
+            return Value::Number(n);
+        }
+    }
+    if types.contains(&"boolean") && matches!(value, "true" | "false") {
+        return json!(value == "true");
+    }
+    Value::String(value.to_owned())
+}
+
+/// Records a violation of `keyword` if the schema has such a limit and `within` returns false
+fn limit(schema: &Value, keyword: &'static str, pointer: &str, errors: &mut Vec<(String, ViolationKind)>, within: impl Fn(f64) -> bool) {
+    if let Some(limit) = schema.get(keyword).filter(|limit| limit.is_number()) {
+        if !within(limit.as_f64().unwrap_or_default()) {
+            errors.push((pointer.to_owned(), ViolationKind::Limit { keyword, limit: limit.clone() }));
+        }
+    }
+}
+
+/// Escapes a property name for a JSON pointer
+fn escape(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use super::*;
+
+    fn enforcer(spec: Value) -> Enforcer {
+        let spec = Spec { name: "openapi.json".into(), content: Bytes::from(spec.to_string()) };
+        Enforcer::new(&spec, OnViolation::Reject).unwrap()
+    }
+
+    fn pets() -> Enforcer {
+        enforcer(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "pets", "version": "1"},
+            "servers": [{"url": "https://api.example.com/v1"}],
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "parameters": [{"name": "limit", "in": "query", "schema": {"type": "integer", "maximum": 100}}],
+                        "responses": {"200": {"content": {"application/json": {"schema": {
+                            "type": "array", "items": {"$ref": "#/components/schemas/Pet"},
+                        }}}}},
+                    },
+                    "post": {
+                        "requestBody": {"required": true, "content": {"application/json": {"schema": {"$ref": "#/components/schemas/Pet"}}}},
+                        "responses": {"201": {"description": "created"}, "4XX": {"description": "invalid"}},
+                    },
+                },
+                "/pets/{id}": {
+                    "parameters": [{"name": "id", "in": "path", "required": true, "schema": {"type": "integer"}}],
+                    "get": {"responses": {"200": {"description": "a pet"}}},
+                },
+            },
+            "components": {"schemas": {"Pet": {
+                "type": "object",
+                "required": ["id", "name"],
+                "additionalProperties": false,
+                "properties": {
+                    "id": {"type": "integer", "readOnly": true},
+                    "name": {"type": "string", "minLength": 1},
+                    "status": {"type": "string", "enum": ["available", "sold"], "nullable": true},
+                },
+            }}},
+        }))
+    }
+
+    fn messages(violations: Vec<Violation>) -> Vec<String> {
+        violations.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn checks_requests() {
+        let enforcer = pets();
+        assert_eq!(enforcer.operation("GET", "/pets"), None);
+        assert_eq!(enforcer.operation("GET", "/v10/pets"), None);
+        let get = enforcer.operation("GET", "/v1/pets").unwrap();
+        assert_eq!(get.path(), "/pets");
+        assert!(enforcer.check_request(&get, &Request { query: Some("limit=10"), ..Default::default() }).is_empty());
+        assert_eq!(messages(enforcer.check_request(&get, &Request { query: Some("limit=many"), ..Default::default() })),
+                   ["query parameter `limit`: expected integer"]);
+        assert_eq!(messages(enforcer.check_request(&get, &Request { query: Some("limit=1000"), ..Default::default() })),
+                   ["query parameter `limit`: violates `maximum: 100`"]);
+
+        let by_id = enforcer.operation("HEAD", "/v1/pets/abc").unwrap();
+        assert_eq!(messages(enforcer.check_request(&by_id, &Request::default())), ["path parameter `id`: expected integer"]);
+
+        let post = enforcer.operation("post", "/v1/pets").unwrap();
+        let json = vec![("Content-Type", "application/json; charset=utf-8")];
+        let request = |body: &'static str| Request { headers: json.clone(), body: Some(body.as_bytes()), ..Default::default() };
+        assert!(enforcer.check_request(&post, &request(r#"{"name": "Rex", "status": null}"#)).is_empty());
+        assert_eq!(messages(enforcer.check_request(&post, &request(r#"{"name": "", "status": "lost", "age": 3}"#))), [
+            "request body at /age: unexpected property `age`",
+            "request body at /name: violates `minLength: 1`",
+            "request body at /status: is not an allowed value",
+        ]);
+        assert_eq!(messages(enforcer.check_request(&post, &request(""))), ["request body: is required"]);
+        assert_eq!(messages(enforcer.check_request(&post, &request("{"))).len(), 1);
+        let xml = Request { headers: vec![("content-type", "application/xml")], body: Some(b"<pet/>"), ..Default::default() };
+        assert_eq!(messages(enforcer.check_request(&post, &xml)), [r#"request body: undocumented content type "application/xml""#]);
+    }
+
+    #[test]
+    fn checks_responses() {
+        let enforcer = pets();
+        let get = enforcer.operation("GET", "/v1/pets").unwrap();
+        let response = |status, body: &'static str| Response { status, content_type: Some("application/json"), body: body.as_bytes() };
+        assert!(enforcer.check_response(&get, &response(200, r#"[{"id": 1, "name": "Rex"}]"#)).is_empty());
+        assert_eq!(messages(enforcer.check_response(&get, &response(200, r#"[{"name": "Rex"}, {"id": 1.5, "name": "Fido"}]"#))), [
+            "response body at /0/id: is required",
+            "response body at /1/id: expected integer",
+        ]);
+        assert_eq!(messages(enforcer.check_response(&get, &response(500, ""))), ["response status: 500 is not documented"]);
+
+        let post = enforcer.operation("POST", "/v1/pets").unwrap();
+        assert!(enforcer.check_response(&post, &response(422, r#"{"error": "invalid"}"#)).is_empty());
+    }
+
+    #[test]
+    fn rejects_or_logs() {
+        let enforcer = pets();
+        let post = enforcer.operation("POST", "/v1/pets").unwrap();
+        let request = Request { headers: vec![("Content-Type", "text/plain")], body: Some(b"Rex"), ..Default::default() };
+        let rejection = enforcer.enforce_request(&post, &request).unwrap_err();
+        assert_eq!(rejection.status, 415);
+        let body: Value = serde_json::from_slice(&rejection.body).unwrap();
+        assert_eq!(body["violations"], json!([r#"request body: undocumented content type "text/plain""#]));
+
+        let rejection = enforcer.enforce_response(&post, &Response { status: 500, ..Default::default() }).unwrap_err();
+        assert_eq!(rejection.status, 500);
+
+        let enforcer = Enforcer { on_violation: OnViolation::Log, ..enforcer.with_base_path("/api/") };
+        let post = enforcer.operation("POST", "/api/pets").unwrap();
+        assert_eq!(enforcer.enforce_request(&post, &request), Ok(()));
+
+        let enforcer = enforcer.with_body_limit(16);
+        assert!(enforcer.checks_body(Some(16)));
+        assert!(!enforcer.checks_body(Some(17)));
+        assert!(!enforcer.checks_body(None));
+
+        let enforcer = Enforcer { on_violation: OnViolation::Reject, ..enforcer };
+        assert!(enforcer.checks_body(Some(17)));
+        assert!(enforcer.checks_body(None));
+        let rejection = enforcer.body_too_large(Location::RequestBody);
+        assert_eq!(rejection.status, 413);
+        let body: Value = serde_json::from_slice(&rejection.body).unwrap();
+        assert_eq!(body["violations"], json!(["request body is larger than 16 bytes"]));
+        assert_eq!(enforcer.body_too_large(Location::ResponseBody).status, 500);
+    }
+
+    #[test]
+    fn checks_swagger2_parameters() {
+        let spec = Spec {
+            name: "openapi.json".into(),
+            content: Bytes::from(std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/examples/openapi.json")).unwrap()),
+        };
+        let enforcer = Enforcer::new(&spec, OnViolation::Reject).unwrap();
+        let find = enforcer.operation("GET", "/v2/pet/findByStatus").unwrap();
+        assert!(enforcer.check_request(&find, &Request { query: Some("status=available,sold"), ..Default::default() }).is_empty());
+        assert_eq!(messages(enforcer.check_request(&find, &Request { query: Some("status=lost"), ..Default::default() })),
+                   ["query parameter `status` at /0: is not an allowed value"]);
+        assert_eq!(messages(enforcer.check_request(&find, &Request::default())), ["query parameter `status`: is required"]);
+    }
+}
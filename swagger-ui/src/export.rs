@@ -0,0 +1,168 @@
+//! Export of swagger-ui as a static site, which can be served by any static file server,
+//! or as a single html file.
+
+use std::collections::HashSet;
+use std::io;
+use std::path::{Component, Path};
+use crate::{Config, Options, ParseError, Spec, UrlObject};
+
+const CONFIG_PATH: &str = "swagger-ui-config.json";
+
+/// Writes swagger-ui `Assets`, generated files of `options`, `swagger-ui-config.json`
+/// and `specs` into `dir`, creating it if needed.
+///
+/// All urls are relative to `dir`, so the site can be served from any path.
+/// Unless set in `config`, a single spec becomes `url` and several specs become `urls`.
+/// Fails without writing anything if two specs, or a spec and a generated file, have the same path.
+pub fn export_dir(dir: impl AsRef<Path>, specs: &[Spec], config: &Config, options: &Options) -> io::Result<()> {
+    let dir = dir.as_ref();
+    let mut paths = options.paths().into_iter().collect::<HashSet<_>>();
+    paths.insert(CONFIG_PATH.to_owned());
+    for spec in specs {
+        let path = Path::new(spec.name.trim_start_matches('/')).components()
+            .filter(|c| *c != Component::CurDir)
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        if !paths.insert(path.clone()) {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("duplicate file path {:?}", path)));
+        }
+    }
+    for path in options.paths() {
+        if let Some(content) = options.file(&path) {
+            write(dir, &path, &content)?;
+        }
+    }
+    for spec in specs {
+        write(dir, &spec.name, &spec.content)?;
+    }
+    let config = serde_json::to_vec_pretty(&site_config(specs, config))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    write(dir, CONFIG_PATH, &config)
+}
+
+/// Generates a single html page with swagger-ui styles, scripts, `config` and `spec` inlined,
+/// which doesn't reference any other file. YAML specs are converted to JSON.
+///
+/// Images of `options.theme` set with `Image::Url` are still loaded from their url.
+pub fn export_html(spec: &Spec, config: &Config, options: &Options) -> Result<String, ParseError> {
+    let spec = spec.parse()?;
+    let config = serde_json::to_value(config)?;
+    Ok(crate::index::standalone_html(options, &config, &spec))
+}
+
+/// Returns `config` pointing to `specs` with relative urls
+pub(crate) fn site_config(specs: &[Spec], config: &Config) -> Config {
+    let mut config = config.clone();
+    let url = |spec: &Spec| format!("./{}", spec.name.trim_start_matches('/'));
+    match specs {
+        [spec] if config.url.is_empty() && config.urls.is_empty() => config.url = url(spec),
+        [_, _, ..] if config.urls.is_empty() => {
+            config.urls = specs.iter().map(|spec| UrlObject::new(&spec.name, &url(spec))).collect();
+        }
+        _ => {}
+    }
+    config
+}
+
+/// Writes `content` to `path` relative to `dir`, refusing paths which could escape it
+fn write(dir: &Path, path: &str, content: &[u8]) -> io::Result<()> {
+    let relative = Path::new(path.trim_start_matches('/'));
+    if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("invalid file path {:?}", path)));
+    }
+    let target = dir.join(relative);
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(target, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bytes, Plugin};
+
+    #[test]
+    fn exports_static_site() {
+        let dir = std::env::temp_dir().join(format!("swagger-ui-export-{}", std::process::id()));
+        let specs = [
+            crate::swagger_spec_file!("../examples/openapi.json"),
+            Spec { name: "v2/openapi.yaml".into(), content: Bytes::from_static(b"openapi: 3.0.0\n") },
+        ];
+        let options = Options {
+            plugins: vec![Plugin::new("noop", "function() { return {}; }").unwrap()],
+            ..Default::default()
+        };
+        export_dir(&dir, &specs, &Config::default(), &options).unwrap();
+
+        for file in ["index.html", "index.css", "swagger-ui-bundle.js", "plugins/noop.js", "openapi.json", "v2/openapi.yaml"] {
+            assert!(dir.join(file).is_file(), "{} is missing", file);
+        }
+        let config: Config = serde_json::from_slice(&std::fs::read(dir.join("swagger-ui-config.json")).unwrap()).unwrap();
+        let urls = config.urls.iter().map(|u| u.url.as_str()).collect::<Vec<_>>();
+        assert_eq!(urls, ["./openapi.json", "./v2/openapi.yaml"]);
+        std::fs::remove_dir_all(dir).unwrap();
+
+        let config = site_config(&specs[..1], &Config::default());
+        assert_eq!(config.url, "./openapi.json");
+    }
+
+    #[test]
+    fn exports_single_html() {
+        let spec = Spec {
+            name: "openapi.yaml".into(),
+            content: Bytes::from_static(b"openapi: 3.0.0\ninfo:\n  title: \"</script>\"\n"),
+        };
+        let options = Options {
+            theme: crate::Theme {
+                logo: Some(crate::Image::File { name: "logo.svg".into(), content: Bytes::from_static(b"<svg/>") }),
+                ..Default::default()
+            },
+            plugins: vec![Plugin::new("noop", "function() { return {}; }").unwrap()],
+            ..Default::default()
+        };
+        let html = export_html(&spec, &Config { deep_linking: true, ..Default::default() }, &options).unwrap();
+
+        assert!(html.contains(r#"spec: {"info":{"title":"<\/script>"},"openapi":"3.0.0"}"#));
+        assert!(html.contains(r#""deepLinking": true"#));
+        assert!(html.contains("data:image/svg+xml;base64,PHN2Zy8+"));
+        assert!(html.contains("function() { return {}; }"));
+        assert!(!html.contains("configUrl"));
+
+        // Only scripts content may mention urls, as strings
+        let markup = html.split("<script>")
+            .map(|part| part.split_once("</script>").map_or(part, |(_, rest)| rest))
+            .collect::<String>();
+        for pattern in ["src=\"", "href=\"", "url("] {
+            for (i, _) in markup.match_indices(pattern) {
+                let value = markup[i + pattern.len()..].trim_start_matches('"');
+                assert!(value.starts_with("data:"), "external resource: {}", &markup[i..(i + 60).min(markup.len())]);
+            }
+        }
+    }
+
+    #[test]
+    fn refuses_duplicate_paths() {
+        let dir = std::env::temp_dir().join(format!("swagger-ui-export-duplicate-{}", std::process::id()));
+        let spec = |name: &str| Spec { name: name.to_owned().into(), content: Bytes::from_static(b"{}") };
+        for specs in [
+            [spec("v1/openapi.json"), spec("./v1/openapi.json")],
+            [spec("openapi.json"), spec("index.html")],
+            [spec("openapi.json"), spec("swagger-ui-config.json")],
+        ] {
+            let err = export_dir(&dir, &specs, &Config::default(), &Options::default()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+            assert!(!dir.exists());
+        }
+    }
+
+    #[test]
+    fn refuses_paths_outside() {
+        let dir = std::env::temp_dir().join(format!("swagger-ui-export-outside-{}", std::process::id()));
+        let specs = [Spec { name: "../openapi.json".into(), content: Bytes::from_static(b"{}") }];
+        let err = export_dir(&dir, &specs, &Config::default(), &Options::default()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
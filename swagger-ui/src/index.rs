@@ -0,0 +1,196 @@
+use serde_json::Value;
+use crate::plugin::{Interceptor, CHAIN_FN, PLUGINS, PRESETS};
+use crate::Options;
+
+/// Whether `swagger-ui-standalone-preset.js` is embedded, which provides the top bar layout
+const STANDALONE: bool = cfg!(any(feature = "standalone-preset", feature = "all-assets"));
+
+/// Generates `index.html`, which loads swagger-ui with `swagger-ui-config.json`
+/// from the same mount and applies `Options::theme` and plugins.
+/// The initializer script gets the `nonce` attribute, if any.
+pub(crate) fn index_html(options: &Options, nonce: Option<&str>) -> String {
+    render(options, None, nonce)
+}
+
+/// Generates a self-contained page, with assets, `config` and `spec` inlined.
+/// `config` must be a JSON object, its `url` and `urls` are ignored.
+pub(crate) fn standalone_html(options: &Options, config: &Value, spec: &Value) -> String {
+    render(options, Some((config, spec)), None)
+}
+
+fn render(options: &Options, inline: Option<(&Value, &Value)>, nonce: Option<&str>) -> String {
+    let theme = &options.theme;
+    let title = theme.title.as_deref().unwrap_or("Swagger UI");
+    let file = |path: &str| options.file(path)
+        .map(|content| String::from_utf8_lossy(&content).into_owned())
+        .unwrap_or_default();
+
+    let favicon = match (&theme.favicon, inline) {
+        (Some(favicon), None) => format!(r#"<link rel="icon" href="{}" />"#, escape_html(&favicon.url())),
+        (Some(favicon), Some(_)) => format!(r#"<link rel="icon" href="{}" />"#, escape_html(&favicon.data_url())),
+        (None, None) => concat!(
+            r#"<link rel="icon" type="image/png" href="./favicon-32x32.png" sizes="32x32" />"#, "\n    ",
+            r#"<link rel="icon" type="image/png" href="./favicon-16x16.png" sizes="16x16" />"#,
+        ).to_owned(),
+        (None, Some(_)) => {
+            let favicon = options.file("favicon-32x32.png").unwrap_or_default();
+            format!(r#"<link rel="icon" type="image/png" href="{}" sizes="32x32" />"#, data_url("favicon-32x32.png", &favicon))
+        }
+    };
+
+    let stylesheets = match inline {
+        None => concat!(
+            r#"<link rel="stylesheet" type="text/css" href="./swagger-ui.css" />"#, "\n    ",
+            r#"<link rel="stylesheet" type="text/css" href="./index.css" />"#,
+        ).to_owned(),
+        Some(_) => format!(
+            "<style>\n{}\n{}</style>",
+            inline_script(&file("swagger-ui.css")),
+            inline_script(&theme.stylesheet(true)),
+        ),
+    };
+
+    let mut scripts = vec!["swagger-ui-bundle.js".to_owned()];
+    if STANDALONE {
+        scripts.push("swagger-ui-standalone-preset.js".to_owned());
+    }
+    scripts.extend(options.presets.iter().map(|p| format!("presets/{}.js", p.name())));
+    scripts.extend(options.plugins.iter().map(|p| format!("plugins/{}.js", p.name())));
+    let scripts = scripts.iter()
+        .map(|path| match inline {
+            None => format!(r#"<script src="./{}" charset="UTF-8"></script>"#, escape_html(path)),
+            Some(_) => format!("<script>\n{}\n</script>", inline_script(&file(path))),
+        })
+        .collect::<Vec<_>>()
+        .join("\n    ");
+
+    let mut initializer = match inline {
+        None => vec![r#"configUrl: "./swagger-ui-config.json""#.to_owned()],
+        Some((config, spec)) => {
+            let mut initializer = vec![format!("spec: {}", spec)];
+            if let Value::Object(config) = config {
+                initializer.extend(config.iter()
+                    .filter(|(key, _)| *key != "url" && *key != "urls")
+                    .map(|(key, value)| format!("{}: {}", js_string(key), value)));
+            }
+            initializer
+        }
+    };
+    initializer.extend([
+        r##"dom_id: "#swagger-ui""##.to_owned(),
+        if STANDALONE {
+            format!("presets: [SwaggerUIBundle.presets.apis, SwaggerUIStandalonePreset].concat(window.{} || [])", PRESETS)
+        } else {
+            format!("presets: [SwaggerUIBundle.presets.apis].concat(window.{} || [])", PRESETS)
+        },
+        format!("plugins: [SwaggerUIBundle.plugins.DownloadUrl].concat(window.{} || [])", PLUGINS),
+        format!("layout: {}", if STANDALONE { r#""StandaloneLayout""# } else { r#""BaseLayout""# }),
+    ]);
+    let interceptors = [
+        ("requestInterceptor", options.request_interceptors()),
+        ("responseInterceptor", options.response_interceptors.clone()),
+    ];
+    for (name, interceptors) in interceptors.iter().filter(|(_, i)| !i.is_empty()) {
+        initializer.push(format!("{}: {}", name, chain(interceptors)));
+    }
+    let helpers = if interceptors.iter().any(|(_, i)| !i.is_empty()) {
+        format!("\n      {}\n", CHAIN_FN)
+    } else {
+        String::new()
+    };
+
+    format!(r##"<!DOCTYPE html>
+<html lang="en">
+  <head>
+    <meta charset="UTF-8">
+    <title>{title}</title>
+    {stylesheets}
+    {favicon}
+  </head>
+  <body>
+    <div id="swagger-ui"></div>
+    {scripts}
+    <script{nonce}>{helpers}
+      window.onload = function() {{
+        window.ui = SwaggerUIBundle({{
+          {initializer}
+        }});
+      }};
+    </script>
+  </body>
+</html>
+"##,
+        title = escape_html(title),
+        stylesheets = stylesheets,
+        favicon = favicon,
+        scripts = scripts,
+        nonce = nonce.map(|n| format!(r#" nonce="{}""#, escape_html(n))).unwrap_or_default(),
+        helpers = inline_script(&helpers),
+        initializer = inline_script(&initializer.join(",\n          ")),
+    )
+}
+
+fn chain(interceptors: &[Interceptor]) -> String {
+    let functions = interceptors.iter()
+        .map(|i| format!("            ({})", i.source))
+        .collect::<Vec<_>>()
+        .join(",\n");
+    format!("chain([\n{}\n          ])", functions)
+}
+
+/// Prevents a script from closing the `<script>` element it's inlined in
+pub(crate) fn inline_script(script: &str) -> String {
+    script.replace("</", "<\\/")
+}
+
+/// Serializes a string into a JavaScript string literal, safe to be inlined in html
+pub(crate) fn js_string(s: &str) -> String {
+    serde_json::to_string(s)
+        .expect("serializing a string can't fail")
+        .replace('<', "\\u003c")
+}
+
+pub(crate) fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Encodes `content` as a `data:` url, guessing its type from `name`
+pub(crate) fn data_url(name: &str, content: &[u8]) -> String {
+    let mime = match name.rsplit('.').next().unwrap_or_default() {
+        "png" => "image/png",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "gif" => "image/gif",
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        _ => "application/octet-stream",
+    };
+    format!("data:{};base64,{}", mime, base64(content))
+}
+
+pub(crate) fn base64(content: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(content.len().div_ceil(3) * 4);
+    for chunk in content.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
@@ -1,12 +1,60 @@
 use std::borrow::Cow;
+use std::path::PathBuf;
 use rust_embed::RustEmbed;
 use serde::{Deserialize, Serialize};
 
 pub use bytes::Bytes;
+pub use cors::Cors;
+pub use overlay::Overlay;
+pub use parse::ParseError;
+pub use plugin::{Interceptor, InvalidPluginName, Plugin};
+pub use request_headers::{HeaderSource, RequestHeader};
+pub use security::{SecurityHeaders, DEFAULT_CSP};
+pub use theme::{Image, Theme};
+pub use versions::Versions;
+#[cfg(feature = "dark-theme")]
+pub use theme::ColorScheme;
 
-/// Assets from swagger-ui-dist
+mod cors;
+pub mod diff;
+#[cfg(feature = "editor")]
+pub mod editor;
+#[cfg(feature = "enforce")]
+pub mod enforce;
+pub mod export;
+mod index;
+pub mod merge;
+#[cfg(any(feature = "mock", feature = "enforce"))]
+mod operation;
+mod overlay;
+mod parse;
+mod plugin;
+pub mod renderer;
+mod request_headers;
+mod security;
+mod theme;
+pub mod versions;
+#[cfg(feature = "bundle")]
+pub mod bundle;
+#[cfg(feature = "changelog")]
+pub mod changelog;
+#[cfg(feature = "convert")]
+pub mod convert;
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(feature = "proxy")]
+pub mod proxy;
+#[cfg(feature = "validate")]
+pub mod validate;
+
+/// Assets from swagger-ui-dist.
+///
+/// Only files used by the generated index page are embedded by default:
+/// `swagger-ui-bundle.js`, `swagger-ui.css`, favicons, the OAuth2 redirect page and,
+/// with `standalone-preset` feature, `swagger-ui-standalone-preset.js`.
+/// Enable `source-maps` feature to add their source maps, or `all-assets` to embed the whole dist folder.
 #[derive(RustEmbed)]
-#[folder = "$CARGO_MANIFEST_DIR/.dist"]
+#[folder = "$OUT_DIR/dist"]
 pub struct Assets;
 
 /// Contains a named url.
@@ -117,6 +165,9 @@ pub struct Config {
     /// your web ui. If this field is populated, the `url` field is not used.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub urls: Vec<UrlObject>,
+    /// Name of the `urls` entry shown when the web ui is first opened, the first one by default.
+    #[serde(default, rename = "urls.primaryName", skip_serializing_if = "String::is_empty")]
+    pub urls_primary_name: String,
 
     // display options:
     /// If set to true, enables deep linking for tags and operations. See the
@@ -172,6 +223,7 @@ impl Default for Config {
         Self {
             url: String::new(),
             urls: vec![],
+            urls_primary_name: String::new(),
             deep_linking: false,
             display_operation_id: false,
             default_model_expand_depth: 1,
@@ -187,22 +239,172 @@ impl Default for Config {
     }
 }
 
+/// Server-side options used by the web framework adapters.
+/// Unlike `Config`, these are never sent to swagger-ui.
+///
+/// Some fields depend on enabled features, so it's non exhaustive: start from `Options::default()`
+/// and set its fields.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct Options {
+    /// Extra files served next to `Assets`, taking precedence over them.
+    pub overlay: Overlay,
+    /// Branding of the generated index page.
+    pub theme: Theme,
+    /// Plugins passed to swagger-ui, after the default ones.
+    pub plugins: Vec<Plugin>,
+    /// Presets passed to swagger-ui, after the default ones.
+    pub presets: Vec<Plugin>,
+    /// Functions chained into swagger-ui `requestInterceptor`.
+    pub request_interceptors: Vec<Interceptor>,
+    /// Functions chained into swagger-ui `responseInterceptor`.
+    pub response_interceptors: Vec<Interceptor>,
+    /// Headers added to requests made by swagger-ui, before `request_interceptors` run.
+    pub request_headers: Vec<RequestHeader>,
+    /// Directory to read swagger-ui-dist files from on each request instead of `Assets`,
+    /// e.g. a patched swagger-ui build, which can then be changed without recompiling.
+    /// Meant for development: files missing from it are not served.
+    pub assets_dir: Option<PathBuf>,
+    /// Security headers sent with the files of the mount, including a `Content-Security-Policy` for html pages.
+    pub security_headers: SecurityHeaders,
+    /// CORS policy of the spec and `swagger-ui-config.json`, so other origins can fetch them.
+    /// Default: `None`, no CORS headers are sent.
+    pub cors: Option<Cors>,
+    /// Proxy route forwarding "Try it out" requests to APIs on other origins without CORS.
+    /// Default: `None`, requests are sent directly.
+    #[cfg(feature = "proxy")]
+    pub proxy: Option<proxy::Proxy>,
+    /// Mock mode: the adapters answer requests to the documented operations of the mocked spec
+    /// with responses synthesized from its examples and schemas, and serve it in place of the spec,
+    /// pointing "Try it out" to the mock. Create it with `mock::Mock::new`, which fails if the spec can't be parsed.
+    /// Default: `None`, no mock is served.
+    #[cfg(feature = "mock")]
+    pub mock: Option<mock::Mock>,
+    /// Changelog page served at `changelog.html`, see `changelog::Changelog`.
+    /// Default: `None`, no changelog is served.
+    #[cfg(feature = "changelog")]
+    pub changelog: Option<changelog::Changelog>,
+}
+
+/// A file served under the swagger-ui mount, with the headers to send along
+#[derive(Debug, Clone)]
+pub struct FileResponse {
+    /// File content
+    pub content: Bytes,
+    /// Extra headers, besides `Content-Type`
+    pub headers: Vec<(&'static str, String)>,
+}
+
+impl Options {
+    /// Returns content of a file served under the swagger-ui mount,
+    /// looking it up in the overlay first, then in generated files
+    /// (`index.html`, `index.css`, theme images and plugins) and in `Assets` or `assets_dir`.
+    pub fn file(&self, path: &str) -> Option<Bytes> {
+        let path = path.trim_start_matches('/');
+        self.overlay.get(path)
+            .or_else(|| self.generated_file(path))
+            .or_else(|| match &self.assets_dir {
+                Some(dir) => overlay::read_file(dir, path),
+                None => Assets::get(path).map(overlay::cow_to_bytes),
+            })
+    }
+
+    /// Same as `file`, along with `security_headers`.
+    /// The generated `index.html` gets a new `Content-Security-Policy` nonce on each call.
+    pub fn file_response(&self, path: &str) -> Option<FileResponse> {
+        let path = path.trim_start_matches('/');
+        let generated_index = path == "index.html" && self.overlay.get(path).is_none();
+        if generated_index && self.security_headers.uses_nonce() {
+            let nonce = security::nonce();
+            return Some(FileResponse {
+                content: Bytes::from(index::index_html(self, Some(&nonce))),
+                headers: self.security_headers.headers(path, Some(&nonce)),
+            });
+        }
+        self.file(path).map(|content| FileResponse {
+            content,
+            headers: self.security_headers.headers(path, None),
+        })
+    }
+
+    /// Returns paths of all files served under the swagger-ui mount, except the spec and config
+    pub fn paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = match &self.assets_dir {
+            Some(dir) => overlay::list_dir(dir),
+            None => Assets::iter().map(|path| path.into_owned()).collect(),
+        };
+        paths.extend(["index.html".to_owned(), "index.css".to_owned()]);
+        paths.extend(self.theme.paths());
+        paths.extend(self.plugins.iter().map(|p| format!("plugins/{}.js", p.name())));
+        paths.extend(self.presets.iter().map(|p| format!("presets/{}.js", p.name())));
+        paths.extend(self.overlay.paths());
+        #[cfg(feature = "changelog")]
+        if self.changelog.is_some() {
+            paths.push(changelog::CHANGELOG_PATH.to_owned());
+        }
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+
+    /// Request interceptors, including the one adding `request_headers`
+    pub fn request_interceptors(&self) -> Vec<Interceptor> {
+        let mut interceptors = vec![];
+        if !self.request_headers.is_empty() {
+            interceptors.push(request_headers::interceptor(&self.request_headers));
+        }
+        interceptors.extend(self.request_interceptors.iter().cloned());
+        #[cfg(feature = "proxy")]
+        if let Some(proxy) = &self.proxy {
+            interceptors.push(proxy.interceptor());
+        }
+        interceptors
+    }
+
+    fn generated_file(&self, path: &str) -> Option<Bytes> {
+        match path {
+            "index.html" => Some(Bytes::from(index::index_html(self, None))),
+            "index.css" => Some(Bytes::from(self.theme.stylesheet(false))),
+            #[cfg(feature = "changelog")]
+            changelog::CHANGELOG_PATH if self.changelog.is_some() => self.changelog.as_ref().map(changelog::Changelog::html),
+            _ => self.theme.file(path).or_else(|| self.plugin_file(path)),
+        }
+    }
+
+    fn plugin_file(&self, path: &str) -> Option<Bytes> {
+        let (dir, file) = path.split_once('/')?;
+        let name = file.strip_suffix(".js")?;
+        let (plugins, registry) = match dir {
+            "plugins" => (&self.plugins, plugin::PLUGINS),
+            "presets" => (&self.presets, plugin::PRESETS),
+            _ => return None,
+        };
+        plugins.iter()
+            .find(|p| p.name() == name)
+            .map(|p| Bytes::from(p.script(registry)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::Path;
     use crate::Assets;
 
-    fn asset_list() -> [&'static str; 8] {
-        [
+    fn asset_list() -> Vec<&'static str> {
+        let mut assets = vec![
             "favicon-16x16.png",
             "favicon-32x32.png",
-            "index.html",
             "oauth2-redirect.html",
             "swagger-ui.css",
-            "swagger-ui.js",
             "swagger-ui-bundle.js",
-            "swagger-ui-standalone-preset.js",
-        ]
+        ];
+        if cfg!(any(feature = "standalone-preset", feature = "all-assets")) {
+            assets.push("swagger-ui-standalone-preset.js");
+        }
+        if cfg!(feature = "all-assets") {
+            assets.extend(["index.html", "swagger-ui.js"]);
+        }
+        assets
     }
 
     #[test]
@@ -215,6 +417,21 @@ mod tests {
         }
     }
 
+    #[cfg(not(any(feature = "source-maps", feature = "all-assets")))]
+    #[test]
+    fn swagger_ui_assets_footprint() {
+        // swagger-ui-bundle.js is about 1.4 MiB, the rest adds up to a few hundred KiB
+        const MAX_SIZE: usize = 3 * 1024 * 1024;
+
+        let mut size = 0;
+        for asset in Assets::iter() {
+            assert!(!asset.ends_with(".map"), "{} is embedded", asset);
+            assert!(asset != "swagger-ui.js" && !asset.contains("es-bundle"), "{} is embedded", asset);
+            size += Assets::get(&asset).unwrap().len();
+        }
+        assert!(size < MAX_SIZE, "assets take {} bytes", size);
+    }
+
     #[test]
     fn swagger_ui_macro_name() {
         let spec = swagger_spec_file!("../examples/openapi.json");
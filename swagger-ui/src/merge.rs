@@ -0,0 +1,557 @@
+//! Merging of several OpenAPI 3 specs into one document, e.g. for a gateway in front of several services.
+//!
+//! Paths of each spec can be prefixed with where the gateway routes them, and tags namespaced with the
+//! service name. Components with the same name and content are shared; conflicting ones are renamed
+//! after their spec (`pets_Error`), and the `$ref`s and security requirements using them are rewritten.
+//! Conflicting `operationId`s are renamed the same way (`pets_list`), along with the links using them.
+//! Root `servers` of the merged specs are dropped, paths are relative to the servers of the merged document.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use serde_json::{json, Map, Value};
+use crate::{Bytes, ParseError, Spec};
+
+const METHODS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// Error returned by [`Merger::merge`]
+#[derive(Debug)]
+pub enum MergeError {
+    /// Failed to parse a spec
+    Parse { name: String, error: ParseError },
+    /// Spec is not an OpenAPI 3 document; Swagger 2.0 specs are converted with the `convert` feature
+    UnsupportedVersion { name: String },
+    /// Two specs define the same path with different values for `key`, e.g. the same operation
+    PathConflict { path: String, key: String },
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::Parse { name, error } => write!(f, "failed to parse {}: {}", name, error),
+            MergeError::UnsupportedVersion { name } => write!(f, "{} is not an OpenAPI 3 spec", name),
+            MergeError::PathConflict { path, key } => write!(f, "conflicting `{}` for path {}", key, path),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// A spec to merge, with how its paths and tags are changed
+#[derive(Debug, Clone)]
+pub struct MergeSource {
+    spec: Spec,
+    path_prefix: String,
+    tag_namespace: Option<String>,
+}
+
+impl MergeSource {
+    /// Create a new `MergeSource` keeping the paths and tags of `spec`
+    pub fn new(spec: Spec) -> Self {
+        Self { spec, path_prefix: String::new(), tag_namespace: None }
+    }
+
+    /// Prefixes every path of the spec with `prefix`, e.g. `/pets` for `/{id}` to become `/pets/{id}`
+    pub fn with_path_prefix(mut self, prefix: impl Into<String>) -> Self {
+        let prefix = prefix.into();
+        let prefix = prefix.trim_end_matches('/');
+        self.path_prefix = if prefix.is_empty() || prefix.starts_with('/') { prefix.to_owned() } else { format!("/{}", prefix) };
+        self
+    }
+
+    /// Prefixes every tag of the spec with `namespace` (`pets.admin`).
+    /// Operations without tags are tagged with `namespace`, so they are grouped together.
+    /// The namespace also names the spec in renamed components.
+    pub fn with_tag_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.tag_namespace = Some(namespace.into());
+        self
+    }
+
+    /// Name used to rename conflicting components and operation ids: the tag namespace, or the spec file name
+    fn ident(&self) -> String {
+        let name = self.tag_namespace.clone().unwrap_or_else(|| {
+            let path = Path::new(self.spec.name.as_ref());
+            path.file_stem().and_then(|s| s.to_str()).unwrap_or("spec").to_owned()
+        });
+        name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' }).collect()
+    }
+
+    fn tag(&self, tag: &Value) -> Value {
+        match (&self.tag_namespace, tag.as_str()) {
+            (Some(namespace), Some(tag)) => json!(format!("{}.{}", namespace, tag)),
+            _ => tag.clone(),
+        }
+    }
+}
+
+/// Merges specs into a single one, in the order they are added
+#[derive(Debug, Clone)]
+pub struct Merger {
+    name: Cow<'static, str>,
+    info: Option<(String, String)>,
+    servers: Vec<String>,
+    sources: Vec<MergeSource>,
+}
+
+impl Merger {
+    /// Create a new `Merger` producing a JSON spec named `name`
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self { name: name.into(), info: None, servers: vec![], sources: vec![] }
+    }
+
+    /// Sets the title and version of the merged spec, taken from the first spec by default
+    pub fn with_info(mut self, title: impl Into<String>, version: impl Into<String>) -> Self {
+        self.info = Some((title.into(), version.into()));
+        self
+    }
+
+    /// Adds a server to the merged spec, e.g. the gateway URL
+    pub fn with_server(mut self, url: impl Into<String>) -> Self {
+        self.servers.push(url.into());
+        self
+    }
+
+    /// Adds a spec to merge
+    pub fn with_spec(mut self, source: MergeSource) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Merges the added specs
+    pub fn merge(&self) -> Result<Spec, MergeError> {
+        let mut merged = Map::new();
+        let mut tags = vec![];
+        let mut paths = Map::new();
+        let mut components = Map::new();
+        let mut operation_ids = HashSet::new();
+
+        for source in &self.sources {
+            let document = parse(&source.spec)?;
+            if merged.is_empty() {
+                for (key, value) in document.as_object().into_iter().flatten() {
+                    if !matches!(key.as_str(), "servers" | "security" | "tags" | "paths" | "components") {
+                        merged.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            let security = document.get("security");
+            let mut items = vec![];
+            for (path, item) in document.get("paths").and_then(Value::as_object).into_iter().flatten() {
+                let mut item = item.clone();
+                for (key, operation) in item.as_object_mut().into_iter().flatten() {
+                    if METHODS.contains(&key.as_str()) {
+                        update_operation(operation, source, security);
+                    }
+                }
+                let path = match path.as_str() {
+                    "/" if !source.path_prefix.is_empty() => source.path_prefix.clone(),
+                    _ => format!("{}{}", source.path_prefix, path),
+                };
+                items.push((path, item));
+            }
+            let id_renames = operation_id_renames(&items, &paths, &operation_ids, &source.ident());
+            let renames = renames(&document, &components, &source.ident(), &id_renames);
+
+            for (kind, values) in document.get("components").and_then(Value::as_object).into_iter().flatten() {
+                let merged_values = components.entry(kind.clone()).or_insert_with(|| json!({}));
+                for (name, value) in values.as_object().into_iter().flatten() {
+                    let name = renames.get(&(kind.clone(), name.clone())).unwrap_or(name);
+                    let mut value = value.clone();
+                    rewrite_component(kind, &mut value, &renames, &id_renames);
+                    merged_values[name] = value;
+                }
+            }
+
+            for tag in document.get("tags").and_then(Value::as_array).into_iter().flatten() {
+                let mut tag = tag.clone();
+                tag["name"] = source.tag(&tag["name"]);
+                if !tags.iter().any(|existing: &Value| existing["name"] == tag["name"]) {
+                    tags.push(tag);
+                }
+            }
+
+            for (path, mut item) in items {
+                rewrite_refs(&mut item, &renames);
+                rewrite_security(&mut item, &renames);
+                for (key, operation) in item.as_object_mut().into_iter().flatten() {
+                    if METHODS.contains(&key.as_str()) {
+                        rewrite_link(operation, &id_renames);
+                    }
+                }
+                rewrite_operation_ids(&mut item, &id_renames);
+                operation_ids.extend(operations(&item).filter_map(|(_, operation)| operation_id(operation)).map(str::to_owned));
+                merge_path(&mut paths, path, item)?;
+            }
+        }
+
+        if merged.is_empty() {
+            merged.insert("openapi".to_owned(), json!("3.0.3"));
+        }
+        if let Some((title, version)) = &self.info {
+            let info = merged.entry("info").or_insert_with(|| json!({}));
+            info["title"] = json!(title);
+            info["version"] = json!(version);
+        }
+        if !self.servers.is_empty() {
+            let servers = self.servers.iter().map(|url| json!({ "url": url })).collect();
+            merged.insert("servers".to_owned(), Value::Array(servers));
+        }
+        if !tags.is_empty() {
+            merged.insert("tags".to_owned(), Value::Array(tags));
+        }
+        merged.insert("paths".to_owned(), Value::Object(paths));
+        components.retain(|_, values| values.as_object().is_some_and(|values| !values.is_empty()));
+        if !components.is_empty() {
+            merged.insert("components".to_owned(), Value::Object(components));
+        }
+
+        Ok(Spec {
+            name: self.name.clone(),
+            content: Bytes::from(serde_json::to_vec(&merged).expect("serializing a json value can't fail")),
+        })
+    }
+}
+
+fn parse(spec: &Spec) -> Result<Value, MergeError> {
+    let document = spec.parse().map_err(|error| MergeError::Parse { name: spec.name.to_string(), error })?;
+    #[cfg(feature = "convert")]
+    let document = match document.get("swagger") {
+        Some(_) => crate::convert::convert_value(&document),
+        None => document,
+    };
+    if !document.get("openapi").and_then(Value::as_str).is_some_and(|version| version.starts_with('3')) {
+        return Err(MergeError::UnsupportedVersion { name: spec.name.to_string() });
+    }
+    Ok(document)
+}
+
+/// Components of `document` which conflict with the already merged ones, by kind and name, with their new name.
+///
+/// A component also conflicts when it is identical but references a renamed component or operation, since it differs
+/// once its references are rewritten, so this is repeated until no new conflict is found.
+fn renames(document: &Value, merged: &Map<String, Value>, ident: &str, id_renames: &HashMap<String, String>) -> HashMap<(String, String), String> {
+    let components = document.get("components").and_then(Value::as_object);
+    let mut renames = HashMap::new();
+    loop {
+        let mut renamed = false;
+        for (kind, values) in components.into_iter().flatten() {
+            let merged_values = merged.get(kind).and_then(Value::as_object);
+            for (name, value) in values.as_object().into_iter().flatten() {
+                let key = (kind.clone(), name.clone());
+                if renames.contains_key(&key) {
+                    continue;
+                }
+                let existing = match merged_values.and_then(|values| values.get(name)) {
+                    Some(existing) => existing,
+                    None => continue,
+                };
+                let mut value = value.clone();
+                rewrite_component(kind, &mut value, &renames, id_renames);
+                if &value == existing {
+                    continue;
+                }
+                let taken = |candidate: &str| merged_values.is_some_and(|values| values.contains_key(candidate))
+                    || values.as_object().is_some_and(|values| values.contains_key(candidate))
+                    || renames.iter().any(|((other_kind, _), other)| other_kind == kind && other == candidate);
+                let mut new_name = format!("{}_{}", ident, name);
+                let mut n = 2;
+                while taken(&new_name) {
+                    new_name = format!("{}_{}{}", ident, name, n);
+                    n += 1;
+                }
+                renames.insert(key, new_name);
+                renamed = true;
+            }
+        }
+        if !renamed {
+            return renames;
+        }
+    }
+}
+
+/// Points the references of a component of `kind` to renamed components and operations
+fn rewrite_component(kind: &str, value: &mut Value, renames: &HashMap<(String, String), String>, id_renames: &HashMap<String, String>) {
+    rewrite_refs(value, renames);
+    match kind {
+        "links" => rewrite_link(value, id_renames),
+        _ => rewrite_operation_ids(value, id_renames),
+    }
+}
+
+/// `operationId`s of the path items of a spec which are already used by the merged operations, with their new name.
+/// An operation identical to the merged one of its path keeps its id, since they are merged together.
+/// Path items are compared before their references are rewritten, renamed references conflict anyway.
+fn operation_id_renames(items: &[(String, Value)], paths: &Map<String, Value>, taken: &HashSet<String>, ident: &str) -> HashMap<String, String> {
+    let own_ids = items.iter()
+        .flat_map(|(_, item)| operations(item))
+        .filter_map(|(_, operation)| operation_id(operation))
+        .collect::<HashSet<_>>();
+    let mut renames = HashMap::new();
+    for (path, item) in items {
+        for (method, operation) in operations(item) {
+            let id = match operation_id(operation) {
+                Some(id) if taken.contains(id) && !renames.contains_key(id) => id,
+                _ => continue,
+            };
+            if paths.get(path).and_then(|merged| merged.get(method)) == Some(operation) {
+                continue;
+            }
+            let is_free = |candidate: &str| !taken.contains(candidate)
+                && !own_ids.contains(candidate)
+                && !renames.values().any(|other| other == candidate);
+            let mut new_id = format!("{}_{}", ident, id);
+            let mut n = 2;
+            while !is_free(&new_id) {
+                new_id = format!("{}_{}{}", ident, id, n);
+                n += 1;
+            }
+            renames.insert(id.to_owned(), new_id);
+        }
+    }
+    renames
+}
+
+/// Operations of a path item, by method
+fn operations(item: &Value) -> impl Iterator<Item = (&String, &Value)> {
+    item.as_object().into_iter().flatten().filter(|(key, _)| METHODS.contains(&key.as_str()))
+}
+
+fn operation_id(operation: &Value) -> Option<&str> {
+    operation.get("operationId").and_then(Value::as_str)
+}
+
+/// Renames the `operationId`s of the links of responses
+fn rewrite_operation_ids(value: &mut Value, renames: &HashMap<String, String>) {
+    if renames.is_empty() {
+        return;
+    }
+    match value {
+        Value::Object(object) => {
+            if let Some(links) = object.get_mut("links").and_then(Value::as_object_mut) {
+                links.values_mut().for_each(|link| rewrite_link(link, renames));
+            }
+            object.values_mut().for_each(|value| rewrite_operation_ids(value, renames));
+        }
+        Value::Array(array) => array.iter_mut().for_each(|value| rewrite_operation_ids(value, renames)),
+        _ => {}
+    }
+}
+
+/// Renames the `operationId` of an operation or a link
+fn rewrite_link(value: &mut Value, renames: &HashMap<String, String>) {
+    if let Some(Value::String(id)) = value.get_mut("operationId") {
+        if let Some(new_id) = renames.get(id.as_str()) {
+            *id = new_id.clone();
+        }
+    }
+}
+
+/// Namespaces the tags of `operation` and moves the root security requirements of its spec to it
+fn update_operation(operation: &mut Value, source: &MergeSource, security: Option<&Value>) {
+    let operation = match operation.as_object_mut() {
+        Some(operation) => operation,
+        None => return,
+    };
+    match operation.get_mut("tags").and_then(Value::as_array_mut) {
+        Some(tags) => tags.iter_mut().for_each(|tag| *tag = source.tag(tag)),
+        None => {
+            if let Some(namespace) = &source.tag_namespace {
+                operation.insert("tags".to_owned(), json!([namespace]));
+            }
+        }
+    }
+    if let Some(security) = security {
+        operation.entry("security").or_insert_with(|| security.clone());
+    }
+}
+
+fn merge_path(paths: &mut Map<String, Value>, path: String, item: Value) -> Result<(), MergeError> {
+    let existing = match paths.get_mut(&path).and_then(Value::as_object_mut) {
+        Some(existing) => existing,
+        None => {
+            paths.insert(path, item);
+            return Ok(());
+        }
+    };
+    for (key, value) in item.as_object().into_iter().flatten() {
+        match existing.get(key) {
+            None => {
+                existing.insert(key.clone(), value.clone());
+            }
+            Some(existing) if existing == value => {}
+            Some(_) => return Err(MergeError::PathConflict { path, key: key.clone() }),
+        }
+    }
+    Ok(())
+}
+
+/// Points `$ref`s and discriminator mappings to renamed components
+fn rewrite_refs(value: &mut Value, renames: &HashMap<(String, String), String>) {
+    if renames.is_empty() {
+        return;
+    }
+    let rename = |reference: &mut String| {
+        let mut parts = reference.strip_prefix("#/components/").unwrap_or_default().splitn(3, '/');
+        let (kind, name, rest) = (parts.next(), parts.next(), parts.next());
+        if let (Some(kind), Some(name)) = (kind, name) {
+            if let Some(new_name) = renames.get(&(kind.to_owned(), name.to_owned())) {
+                *reference = match rest {
+                    Some(rest) => format!("#/components/{}/{}/{}", kind, new_name, rest),
+                    None => format!("#/components/{}/{}", kind, new_name),
+                };
+            }
+        }
+    };
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::String(reference)) = object.get_mut("$ref") {
+                rename(reference);
+            }
+            if let Some(mapping) = object.get_mut("discriminator").and_then(|d| d.get_mut("mapping")).and_then(Value::as_object_mut) {
+                for target in mapping.values_mut() {
+                    if let Value::String(reference) = target {
+                        rename(reference);
+                    }
+                }
+            }
+            object.values_mut().for_each(|value| rewrite_refs(value, renames));
+        }
+        Value::Array(array) => array.iter_mut().for_each(|value| rewrite_refs(value, renames)),
+        _ => {}
+    }
+}
+
+/// Renames security schemes in the security requirements of the operations of a path item
+fn rewrite_security(item: &mut Value, renames: &HashMap<(String, String), String>) {
+    for operation in item.as_object_mut().into_iter().flat_map(|item| item.values_mut()) {
+        for requirement in operation.get_mut("security").and_then(Value::as_array_mut).into_iter().flatten() {
+            if let Some(requirement) = requirement.as_object_mut() {
+                *requirement = std::mem::take(requirement).into_iter()
+                    .map(|(scheme, scopes)| {
+                        let key = ("securitySchemes".to_owned(), scheme);
+                        (renames.get(&key).cloned().unwrap_or(key.1), scopes)
+                    })
+                    .collect();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use super::*;
+
+    fn spec(name: &'static str, document: Value) -> Spec {
+        Spec { name: Cow::Borrowed(name), content: Bytes::from(serde_json::to_vec(&document).unwrap()) }
+    }
+
+    fn service(title: &str, schemas: Value, schemes: Value) -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "info": {"title": title, "version": "1"},
+            "servers": [{"url": "https://internal"}],
+            "tags": [{"name": "admin", "description": title}],
+            "security": [{"auth": []}],
+            "paths": {
+                "/": {"get": {"responses": {"200": {"content": {"application/json": {"schema": {"$ref": "#/components/schemas/Item"}}}}}}},
+                "/{id}": {"delete": {"tags": ["admin"], "responses": {"default": {"$ref": "#/components/responses/Error"}}}},
+            },
+            "components": {
+                "schemas": schemas,
+                "responses": {"Error": {"description": "error", "content": {"application/json": {"schema": {"$ref": "#/components/schemas/Error"}}}}},
+                "securitySchemes": schemes,
+            },
+        })
+    }
+
+    fn merged() -> Value {
+        let error = json!({"type": "object", "properties": {"message": {"type": "string"}}});
+        let pets = service("pets", json!({"Item": {"type": "string"}, "Error": error}), json!({"auth": {"type": "http", "scheme": "basic"}}));
+        let orders = service("orders", json!({"Item": {"type": "integer"}, "Error": error}), json!({"auth": {"type": "http", "scheme": "bearer"}}));
+        Merger::new("gateway.json")
+            .with_info("Gateway", "2")
+            .with_server("https://gateway")
+            .with_spec(MergeSource::new(spec("pets.json", pets)).with_path_prefix("/pets/").with_tag_namespace("pets"))
+            .with_spec(MergeSource::new(spec("orders.json", orders)).with_path_prefix("orders"))
+            .merge().unwrap()
+            .parse().unwrap()
+    }
+
+    #[test]
+    fn prefixes_paths_and_namespaces_tags() {
+        let merged = merged();
+        assert_eq!(merged["info"], json!({"title": "Gateway", "version": "2"}));
+        assert_eq!(merged["servers"], json!([{"url": "https://gateway"}]));
+        let paths = merged["paths"].as_object().unwrap().keys().collect::<Vec<_>>();
+        assert_eq!(paths, ["/orders", "/orders/{id}", "/pets", "/pets/{id}"]);
+        assert_eq!(merged["paths"]["/pets"]["get"]["tags"], json!(["pets"]));
+        assert_eq!(merged["paths"]["/pets/{id}"]["delete"]["tags"], json!(["pets.admin"]));
+        assert_eq!(merged["paths"]["/orders/{id}"]["delete"]["tags"], json!(["admin"]));
+        assert_eq!(merged["tags"], json!([{"name": "pets.admin", "description": "pets"}, {"name": "admin", "description": "orders"}]));
+    }
+
+    #[test]
+    fn renames_conflicting_components() {
+        let merged = merged();
+        let components = &merged["components"];
+        let schemas = components["schemas"].as_object().unwrap().keys().collect::<Vec<_>>();
+        // identical schemas are shared
+        assert_eq!(schemas, ["Error", "Item", "orders_Item"]);
+        assert_eq!(components["responses"].as_object().unwrap().len(), 1);
+        assert_eq!(components["securitySchemes"]["orders_auth"]["scheme"], "bearer");
+
+        let orders = &merged["paths"]["/orders"]["get"];
+        assert_eq!(orders["responses"]["200"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/orders_Item");
+        assert_eq!(orders["security"], json!([{"orders_auth": []}]));
+        assert_eq!(merged["paths"]["/pets"]["get"]["security"], json!([{"auth": []}]));
+        assert!(merged.get("security").is_none());
+    }
+
+    #[test]
+    fn renames_conflicting_operation_ids() {
+        let health = json!({"get": {"operationId": "health", "responses": {"200": {"description": "ok"}}}});
+        let service = |name: &str| json!({
+            "openapi": "3.0.3",
+            "info": {},
+            "paths": {
+                format!("/{}", name): {"get": {"operationId": "list", "responses": {"200": {
+                    "description": name,
+                    "links": {"first": {"operationId": "list"}, "mine": {"$ref": "#/components/links/Mine"}},
+                }}}},
+                "/health": health,
+            },
+            "components": {"links": {"Mine": {"operationId": "list"}}},
+        });
+        let merged = Merger::new("gateway.json")
+            .with_spec(MergeSource::new(spec("pets.json", service("pets"))))
+            .with_spec(MergeSource::new(spec("orders.json", service("orders"))))
+            .merge().unwrap()
+            .parse().unwrap();
+
+        assert_eq!(merged["paths"]["/pets"]["get"]["operationId"], "list");
+        let orders = &merged["paths"]["/orders"]["get"];
+        assert_eq!(orders["operationId"], "orders_list");
+        assert_eq!(orders["responses"]["200"]["links"]["first"]["operationId"], "orders_list");
+        assert_eq!(orders["responses"]["200"]["links"]["mine"]["$ref"], "#/components/links/orders_Mine");
+        assert_eq!(merged["components"]["links"]["orders_Mine"]["operationId"], "orders_list");
+        // identical operations are shared and keep their id
+        assert_eq!(merged["paths"]["/health"]["get"]["operationId"], "health");
+    }
+
+    #[test]
+    fn reports_conflicting_operations() {
+        let pets = spec("pets.json", json!({"openapi": "3.0.3", "info": {}, "paths": {"/health": {"get": {"operationId": "pets"}}}}));
+        let orders = spec("orders.json", json!({"openapi": "3.0.3", "info": {}, "paths": {"/health": {"get": {"operationId": "orders"}}}}));
+        let err = Merger::new("gateway.json")
+            .with_spec(MergeSource::new(pets))
+            .with_spec(MergeSource::new(orders))
+            .merge().unwrap_err();
+        assert!(matches!(&err, MergeError::PathConflict { path, key } if path == "/health" && key == "get"), "{}", err);
+
+        let swagger = spec("swagger.json", json!({"swagger": "2.0", "info": {}, "paths": {}}));
+        let result = Merger::new("gateway.json").with_spec(MergeSource::new(swagger)).merge();
+        assert_eq!(result.is_ok(), cfg!(feature = "convert"));
+    }
+}
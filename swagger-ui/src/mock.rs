@@ -0,0 +1,335 @@
+//! Mock server answering requests to the documented operations of a spec, enabled with the `mock` feature.
+//!
+//! Responses are synthesized from `example`/`examples`, or from schemas when there is no example,
+//! so "Try it out" can be used before the API is implemented.
+
+use serde_json::{json, Map, Value};
+use crate::{Bytes, ParseError, Spec};
+use crate::operation::{find_path, resolve};
+
+/// Path of the mock routes under the swagger-ui mount
+pub const MOCK_PATH: &str = "mock";
+
+/// How deep schemas are expanded, which also stops recursive schemas
+const MAX_DEPTH: usize = 8;
+
+/// A mock of the API described by a spec
+#[derive(Debug, Clone)]
+pub struct Mock {
+    spec: Value,
+}
+
+/// A synthesized response
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockResponse {
+    /// Status code of the documented response
+    pub status: u16,
+    /// Media type of the body, `None` if the response has no content
+    pub content_type: Option<String>,
+    /// Response body
+    pub body: Bytes,
+}
+
+impl Mock {
+    /// Create a new `Mock` of the API described by `spec`
+    pub fn new(spec: &Spec) -> Result<Self, ParseError> {
+        Ok(Self { spec: spec.parse()? })
+    }
+
+    /// Returns the spec with its servers replaced by the mock, served at the absolute path `mock_url`,
+    /// so "Try it out" requests are sent to it. Path and operation `servers` are removed.
+    pub fn spec_content(&self, mock_url: &str) -> Bytes {
+        let mut spec = self.spec.clone();
+        if let Some(spec) = spec.as_object_mut() {
+            if spec.contains_key("swagger") {
+                spec.remove("host");
+                spec.remove("schemes");
+                spec.insert("basePath".to_owned(), json!(mock_url));
+            } else {
+                spec.insert("servers".to_owned(), json!([{ "url": mock_url, "description": "Mock server" }]));
+                let path_items = spec.get_mut("paths").and_then(Value::as_object_mut).into_iter().flat_map(|paths| paths.values_mut());
+                for path_item in path_items.filter_map(Value::as_object_mut) {
+                    path_item.remove("servers");
+                    for operation in path_item.values_mut().filter_map(Value::as_object_mut) {
+                        operation.remove("servers");
+                    }
+                }
+            }
+        }
+        Bytes::from(serde_json::to_vec(&spec).expect("serializing a json value can't fail"))
+    }
+
+    /// Returns the response to a `method` request to `path`, relative to the mock url.
+    /// `None` if no operation is documented for them.
+    pub fn respond(&self, method: &str, path: &str) -> Option<MockResponse> {
+        let method = method.to_ascii_lowercase();
+        let path_item = find_path(&self.spec, path)?.item;
+        let operation = path_item.get(method.as_str())
+            .or_else(|| (method == "head").then(|| path_item.get("get")).flatten())?;
+        let (status, response) = self.documented_response(operation)?;
+        let response = self.resolve(response);
+
+        let (content_type, body) = if self.spec.get("swagger").is_some() {
+            self.swagger2_body(operation, response)
+        } else {
+            self.openapi3_body(response)
+        };
+        let body = match (&content_type, body) {
+            (_, None) => Bytes::new(),
+            (Some(content_type), Some(Value::String(s))) if !content_type.contains("json") => Bytes::from(s),
+            (_, Some(value)) => Bytes::from(serde_json::to_vec_pretty(&value).expect("serializing a json value can't fail")),
+        };
+        Some(MockResponse { status, content_type, body })
+    }
+
+    /// Picks the first success response, then `default`, then any documented one
+    fn documented_response<'a>(&self, operation: &'a Value) -> Option<(u16, &'a Value)> {
+        let responses = operation.get("responses")?.as_object()?;
+        let mut codes = responses.keys().collect::<Vec<_>>();
+        codes.sort_by_key(|code| match code.as_str() {
+            code if code.starts_with('2') && code.parse::<u16>().is_ok() => (0, code.to_string()),
+            "2XX" | "2xx" => (1, String::new()),
+            "default" => (2, String::new()),
+            code => (3, code.to_string()),
+        });
+        let code = codes.first()?;
+        let status = code.parse().unwrap_or(200);
+        Some((status, &responses[code.as_str()]))
+    }
+
+    fn openapi3_body(&self, response: &Value) -> (Option<String>, Option<Value>) {
+        let content = match response.get("content").and_then(Value::as_object) {
+            Some(content) if !content.is_empty() => content,
+            _ => return (None, None),
+        };
+        let (content_type, media) = content.get_key_value("application/json")
+            .or_else(|| content.iter().find(|(content_type, _)| content_type.contains("json")))
+            .or_else(|| content.iter().next())
+            .expect("content is not empty");
+        let body = media.get("example").cloned()
+            .or_else(|| media.get("examples")
+                .and_then(Value::as_object)
+                .and_then(|examples| examples.values().next())
+                .and_then(|example| self.resolve(example).get("value").cloned()))
+            .or_else(|| media.get("schema").map(|schema| self.example(schema, 0)));
+        (Some(content_type.clone()), body)
+    }
+
+    fn swagger2_body(&self, operation: &Value, response: &Value) -> (Option<String>, Option<Value>) {
+        let produces = operation.get("produces").or_else(|| self.spec.get("produces"))
+            .and_then(Value::as_array)
+            .map(|produces| produces.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let produces = produces.iter().find(|content_type| content_type.contains("json"))
+            .or_else(|| produces.first())
+            .copied()
+            .unwrap_or("application/json");
+        if let Some(examples) = response.get("examples").and_then(Value::as_object) {
+            if let Some((content_type, example)) = examples.get_key_value(produces).or_else(|| examples.iter().next()) {
+                return (Some(content_type.clone()), Some(example.clone()));
+            }
+        }
+        match response.get("schema") {
+            Some(schema) => (Some(produces.to_owned()), Some(self.example(schema, 0))),
+            None => (None, None),
+        }
+    }
+
+    /// Synthesizes an example value of `schema`
+    fn example(&self, schema: &Value, depth: usize) -> Value {
+        let schema = self.resolve(schema);
+        if depth > MAX_DEPTH {
+            return Value::Null;
+        }
+        for key in ["example", "default", "const"] {
+            if let Some(value) = schema.get(key) {
+                return value.clone();
+            }
+        }
+        for key in ["examples", "enum", "oneOf", "anyOf"] {
+            if let Some(first) = schema.get(key).and_then(Value::as_array).and_then(|values| values.first()) {
+                return match key {
+                    "oneOf" | "anyOf" => self.example(first, depth + 1),
+                    _ => first.clone(),
+                };
+            }
+        }
+        if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+            let mut merged = Map::new();
+            for schema in all_of {
+                match self.example(schema, depth + 1) {
+                    Value::Object(object) => merged.extend(object),
+                    other if all_of.len() == 1 => return other,
+                    _ => {}
+                }
+            }
+            return Value::Object(merged);
+        }
+
+        let types = match schema.get("type") {
+            Some(Value::String(t)) => vec![t.as_str()],
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        let format = schema.get("format").and_then(Value::as_str).unwrap_or_default();
+        match types.iter().find(|t| **t != "null").copied() {
+            Some("array") => match schema.get("items") {
+                Some(items) => json!([self.example(items, depth + 1)]),
+                None => json!([]),
+            },
+            Some("string") => json!(match format {
+                "date-time" => "1970-01-01T00:00:00Z",
+                "date" => "1970-01-01",
+                "time" => "00:00:00Z",
+                "uuid" => "00000000-0000-0000-0000-000000000000",
+                "email" => "user@example.com",
+                "uri" | "url" => "https://example.com",
+                "hostname" => "example.com",
+                "ipv4" => "192.0.2.1",
+                "ipv6" => "2001:db8::1",
+                "byte" => "ZXhhbXBsZQ==",
+                _ => "string",
+            }),
+            Some("integer") => schema.get("minimum").cloned().unwrap_or(json!(0)),
+            Some("number") => schema.get("minimum").cloned().unwrap_or(json!(0.0)),
+            Some("boolean") => json!(true),
+            Some("object") | None if schema.get("properties").is_some() || types.contains(&"object") => {
+                let properties = schema.get("properties").and_then(Value::as_object);
+                Value::Object(properties.into_iter().flatten()
+                    .filter(|(_, property)| !self.resolve(property).get("writeOnly").and_then(Value::as_bool).unwrap_or(false))
+                    .map(|(name, property)| (name.clone(), self.example(property, depth + 1)))
+                    .collect())
+            }
+            _ => Value::Null,
+        }
+    }
+
+    /// Follows local `$ref`s
+    fn resolve<'a>(&'a self, value: &'a Value) -> &'a Value {
+        resolve(&self.spec, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use super::*;
+
+    fn mock(spec: Value) -> Mock {
+        Mock::new(&Spec { name: "openapi.json".into(), content: Bytes::from(spec.to_string()) }).unwrap()
+    }
+
+    fn json_body(response: &MockResponse) -> Value {
+        serde_json::from_slice(&response.body).unwrap()
+    }
+
+    #[test]
+    fn answers_with_examples() {
+        let mock = mock(json!({
+            "openapi": "3.0.3",
+            "paths": {
+                "/pets/{id}": {
+                    "get": { "responses": {
+                        "404": { "description": "missing" },
+                        "200": { "description": "pet", "content": { "application/json": { "example": { "name": "Rex" } } } }
+                    } },
+                    "delete": { "responses": { "204": { "description": "deleted" } } }
+                },
+                "/pets/mine": {
+                    "get": { "responses": { "200": { "$ref": "#/components/responses/Pets" } } }
+                }
+            },
+            "components": {
+                "responses": { "Pets": { "description": "pets", "content": {
+                    "application/json": { "examples": { "first": { "$ref": "#/components/examples/Pets" } } }
+                } } },
+                "examples": { "Pets": { "value": [{ "name": "Mine" }] } }
+            }
+        }));
+
+        let response = mock.respond("GET", "/pets/42").unwrap();
+        assert_eq!((response.status, response.content_type.as_deref()), (200, Some("application/json")));
+        assert_eq!(json_body(&response), json!({ "name": "Rex" }));
+        assert_eq!(json_body(&mock.respond("GET", "/pets/mine").unwrap()), json!([{ "name": "Mine" }]));
+
+        let response = mock.respond("DELETE", "/pets/42").unwrap();
+        assert_eq!((response.status, response.content_type, response.body.len()), (204, None, 0));
+
+        assert!(mock.respond("POST", "/pets/42").is_none());
+        assert!(mock.respond("GET", "/owners").is_none());
+    }
+
+    #[test]
+    fn synthesizes_from_schemas() {
+        let mock = mock(json!({
+            "openapi": "3.1.0",
+            "paths": { "/pets": { "post": { "responses": { "201": { "description": "created", "content": {
+                "application/json": { "schema": { "type": "array", "items": { "$ref": "#/components/schemas/Pet" } } }
+            } } } } } },
+            "components": { "schemas": {
+                "Pet": { "allOf": [
+                    { "type": "object", "properties": {
+                        "id": { "type": "integer", "format": "int64", "readOnly": true },
+                        "password": { "type": "string", "writeOnly": true }
+                    } },
+                    { "properties": {
+                        "name": { "type": "string" },
+                        "born": { "type": ["string", "null"], "format": "date" },
+                        "status": { "type": "string", "enum": ["available", "sold"] },
+                        "parent": { "$ref": "#/components/schemas/Pet" }
+                    } }
+                ] }
+            } }
+        }));
+
+        let response = mock.respond("post", "/pets").unwrap();
+        assert_eq!(response.status, 201);
+        let pet = &json_body(&response)[0];
+        assert_eq!(pet["id"], json!(0));
+        assert_eq!(pet["name"], json!("string"));
+        assert_eq!(pet["born"], json!("1970-01-01"));
+        assert_eq!(pet["status"], json!("available"));
+        assert!(pet.get("password").is_none());
+        assert!(pet["parent"]["parent"].is_object());
+    }
+
+    #[test]
+    fn mocks_swagger2() {
+        let mock = Mock::new(&crate::swagger_spec_file!("../examples/openapi.json")).unwrap();
+
+        let response = mock.respond("GET", "/pet/findByStatus").unwrap();
+        assert_eq!(response.content_type.as_deref(), Some("application/json"));
+        assert_eq!(json_body(&response)[0]["photoUrls"], json!(["string"]));
+        assert!(mock.respond("GET", "/store/inventory").is_some());
+
+        let spec: Value = serde_json::from_slice(&mock.spec_content("/docs/mock")).unwrap();
+        assert_eq!(spec["basePath"], json!("/docs/mock"));
+        assert!(spec.get("host").is_none());
+    }
+
+    #[test]
+    fn points_all_servers_to_the_mock() {
+        let mock = mock(json!({
+            "openapi": "3.0.3",
+            "servers": [{ "url": "https://api.example.com" }],
+            "paths": { "/pets": {
+                "servers": [{ "url": "https://pets.example.com" }],
+                "get": {
+                    "servers": [{ "url": "https://legacy.example.com" }],
+                    "responses": { "200": { "description": "pets" } }
+                }
+            } }
+        }));
+
+        let spec: Value = serde_json::from_slice(&mock.spec_content("/docs/mock")).unwrap();
+        assert_eq!(spec["servers"], json!([{ "url": "/docs/mock", "description": "Mock server" }]));
+        assert!(spec["paths"]["/pets"].get("servers").is_none());
+        assert!(spec["paths"]["/pets"]["get"].get("servers").is_none());
+    }
+
+    #[test]
+    fn fails_on_invalid_spec() {
+        let spec = Spec { name: "openapi.yaml".into(), content: Bytes::from_static(b"openapi: [") };
+        assert!(Mock::new(&spec).is_err());
+    }
+}
@@ -0,0 +1,53 @@
+//! Lookup of the documented operations of a parsed spec, shared by the mock and the enforcement middleware.
+
+use serde_json::Value;
+
+/// How many `$ref`s are followed in a row, which also stops reference cycles
+const MAX_REFS: usize = 8;
+
+/// A path item matching a request path
+#[cfg_attr(not(feature = "enforce"), allow(dead_code))]
+pub(crate) struct PathMatch<'a> {
+    /// Key of the path item in `paths`, e.g. `/pet/{petId}`
+    pub template: &'a str,
+    /// The path item
+    pub item: &'a Value,
+    /// Values of the templated segments, by parameter name
+    pub params: Vec<(String, String)>,
+}
+
+/// Finds the path item matching `path`, preferring the one with the fewest templated segments
+pub(crate) fn find_path<'a>(spec: &'a Value, path: &str) -> Option<PathMatch<'a>> {
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+    spec.get("paths")?.as_object()?.iter()
+        .filter_map(|(template, item)| {
+            let parts = template.trim_matches('/').split('/').collect::<Vec<_>>();
+            if parts.len() != segments.len() {
+                return None;
+            }
+            let mut params = vec![];
+            for (part, segment) in parts.iter().zip(&segments) {
+                match part.strip_prefix('{').and_then(|part| part.strip_suffix('}')) {
+                    Some(name) if !segment.is_empty() => params.push((name.to_owned(), segment.to_string())),
+                    _ if part != segment => return None,
+                    _ => {}
+                }
+            }
+            Some(PathMatch { template, item: resolve(spec, item), params })
+        })
+        .min_by_key(|path| path.params.len())
+}
+
+/// Follows local `$ref`s
+pub(crate) fn resolve<'a>(spec: &'a Value, mut value: &'a Value) -> &'a Value {
+    for _ in 0..MAX_REFS {
+        match value.get("$ref").and_then(Value::as_str).and_then(|r| r.strip_prefix('#')) {
+            Some(pointer) => match spec.pointer(pointer) {
+                Some(target) => value = target,
+                None => break,
+            },
+            None => break,
+        }
+    }
+    value
+}
@@ -0,0 +1,179 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use rust_embed::RustEmbed;
+use crate::Bytes;
+
+/// Extra files served alongside swagger-ui assets, e.g. a custom logo, stylesheet or plugin.
+///
+/// Sources are looked up in the order they were added, and take precedence over `Assets`,
+/// so an overlay can both add new files and replace bundled ones.
+#[derive(Clone, Default)]
+pub struct Overlay {
+    sources: Vec<Source>,
+}
+
+#[derive(Clone)]
+enum Source {
+    File { name: Cow<'static, str>, content: Bytes },
+    Embed {
+        get: fn(&str) -> Option<Cow<'static, [u8]>>,
+        iter: fn() -> rust_embed::Filenames,
+    },
+    Dir(PathBuf),
+}
+
+impl Overlay {
+    /// Create an empty overlay
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a single file
+    pub fn file(mut self, name: impl Into<Cow<'static, str>>, content: impl Into<Bytes>) -> Self {
+        let name = match name.into() {
+            Cow::Borrowed(name) => Cow::Borrowed(name.trim_start_matches('/')),
+            Cow::Owned(name) => Cow::Owned(name.trim_start_matches('/').to_owned()),
+        };
+        self.sources.push(Source::File { name, content: content.into() });
+        self
+    }
+
+    /// Add all files of a `RustEmbed` type
+    pub fn embed<E: RustEmbed>(mut self) -> Self {
+        self.sources.push(Source::Embed { get: E::get, iter: E::iter });
+        self
+    }
+
+    /// Add files from a directory on disk, which are read on each request
+    pub fn dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.sources.push(Source::Dir(path.into()));
+        self
+    }
+
+    /// Returns `true` if no sources were added
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Returns content of the file at `path`, relative to the swagger-ui mount
+    pub fn get(&self, path: &str) -> Option<Bytes> {
+        let path = path.trim_start_matches('/');
+        self.sources.iter().find_map(|source| match source {
+            Source::File { name, content } => (name == path).then(|| content.clone()),
+            Source::Embed { get, .. } => get(path).map(cow_to_bytes),
+            Source::Dir(dir) => read_file(dir, path),
+        })
+    }
+
+    /// Returns paths of all files in the overlay, reading directories on disk
+    pub fn paths(&self) -> Vec<String> {
+        let mut paths = vec![];
+        for source in &self.sources {
+            match source {
+                Source::File { name, .. } => paths.push(name.to_string()),
+                Source::Embed { iter, .. } => paths.extend(iter().map(|path| path.into_owned())),
+                Source::Dir(dir) => paths.extend(list_dir(dir)),
+            }
+        }
+        paths
+    }
+}
+
+impl fmt::Debug for Overlay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.sources.iter().map(|source| match source {
+                Source::File { name, .. } => format!("file {}", name),
+                Source::Embed { .. } => "embed".to_owned(),
+                Source::Dir(dir) => format!("dir {}", dir.display()),
+            }))
+            .finish()
+    }
+}
+
+pub(crate) fn cow_to_bytes(content: Cow<'static, [u8]>) -> Bytes {
+    match content {
+        Cow::Borrowed(content) => Bytes::from_static(content),
+        Cow::Owned(content) => Bytes::from(content),
+    }
+}
+
+/// Reads `path` from `dir`, refusing paths which could escape it
+pub(crate) fn read_file(dir: &Path, path: &str) -> Option<Bytes> {
+    let relative = Path::new(path);
+    if path.is_empty() || !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    std::fs::read(dir.join(relative)).ok().map(Bytes::from)
+}
+
+/// Returns paths of all files in `dir` and its subdirectories, relative to it
+pub(crate) fn list_dir(dir: &Path) -> Vec<String> {
+    let mut paths = vec![];
+    list_dir_into(dir, "", &mut paths);
+    paths
+}
+
+fn list_dir_into(dir: &Path, prefix: &str, paths: &mut Vec<String>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = format!("{}{}", prefix, name);
+        match entry.file_type() {
+            Ok(t) if t.is_dir() => list_dir_into(&entry.path(), &format!("{}/", path), paths),
+            Ok(_) => paths.push(path),
+            Err(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Assets, Bytes, Options, Overlay};
+
+    #[test]
+    fn overlay_takes_precedence() {
+        let options = Options {
+            overlay: Overlay::new()
+                .file("/logo.svg", Bytes::from_static(b"<svg/>"))
+                .file("swagger-ui.css", Bytes::from_static(b"body {}"))
+                .file("swagger-ui.css", Bytes::from_static(b"ignored")),
+            ..Default::default()
+        };
+        assert_eq!(options.file("logo.svg").unwrap(), "<svg/>");
+        assert_eq!(options.file("/swagger-ui.css").unwrap(), "body {}");
+        assert_eq!(options.file("favicon-16x16.png").unwrap(), Assets::get("favicon-16x16.png").unwrap().as_ref());
+        assert!(options.file("missing.js").is_none());
+    }
+
+    #[test]
+    fn overlay_dir_stays_inside() {
+        let overlay = Overlay::new().dir(concat!(env!("CARGO_MANIFEST_DIR"), "/examples"));
+        assert!(overlay.get("openapi.json").is_some());
+        assert!(overlay.get("../Cargo.toml").is_none());
+        assert!(overlay.get("").is_none());
+    }
+
+    #[test]
+    fn assets_dir_replaces_assets() {
+        let dir = std::env::temp_dir().join(format!("swagger-ui-assets-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("swagger-ui-bundle.js"), "/* patched */").unwrap();
+        let options = Options {
+            assets_dir: Some(dir.clone()),
+            ..Default::default()
+        };
+
+        assert_eq!(options.file("swagger-ui-bundle.js").unwrap(), "/* patched */");
+        std::fs::write(dir.join("swagger-ui-bundle.js"), "/* patched again */").unwrap();
+        assert_eq!(options.file("swagger-ui-bundle.js").unwrap(), "/* patched again */");
+        assert!(options.file("swagger-ui.css").is_none());
+        assert!(options.file("index.html").is_some());
+        assert!(options.paths().contains(&"swagger-ui-bundle.js".to_owned()));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
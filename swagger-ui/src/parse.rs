@@ -0,0 +1,140 @@
+use std::fmt;
+use serde_json::{Map, Number, Value};
+use crate::Spec;
+
+/// Error returned when spec content is neither valid JSON nor valid YAML
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Error description from the underlying parser
+    pub message: String,
+    /// Line of the error (1-based), if known
+    pub line: Option<usize>,
+    /// Column of the error (1-based), if known
+    pub column: Option<usize>,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "{} at line {} column {}", self.message, line, column),
+            _ => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<serde_json::Error> for ParseError {
+    fn from(err: serde_json::Error) -> Self {
+        // serde_json reports line 0 for errors which aren't bound to a position
+        let known = err.line() > 0;
+        let mut message = err.to_string();
+        if let Some(pos) = message.rfind(" at line ") {
+            message.truncate(pos);
+        }
+        Self {
+            message,
+            line: known.then(|| err.line()),
+            column: known.then(|| err.column()),
+        }
+    }
+}
+
+impl From<serde_yaml::Error> for ParseError {
+    fn from(err: serde_yaml::Error) -> Self {
+        let location = err.location();
+        let mut message = err.to_string();
+        if let Some(pos) = message.rfind(" at line ") {
+            message.truncate(pos);
+        }
+        Self {
+            message,
+            line: location.as_ref().map(|l| l.line()),
+            column: location.as_ref().map(|l| l.column()),
+        }
+    }
+}
+
+impl Spec {
+    /// Returns `true` if the spec file name has a `.yaml` or `.yml` extension
+    pub fn is_yaml(&self) -> bool {
+        self.name.ends_with(".yaml") || self.name.ends_with(".yml")
+    }
+
+    /// Parses spec content as YAML or JSON, depending on the spec file name
+    pub fn parse(&self) -> Result<Value, ParseError> {
+        if self.is_yaml() {
+            parse_yaml(&self.content)
+        } else {
+            Ok(serde_json::from_slice(&self.content)?)
+        }
+    }
+}
+
+/// Parses YAML into a json value.
+///
+/// Goes through `serde_yaml::Value`, because YAML allows non-string keys
+/// (e.g. `200:` in `responses`), which `serde_json::Value` can't deserialize directly.
+pub(crate) fn parse_yaml(content: &[u8]) -> Result<Value, ParseError> {
+    let value: serde_yaml::Value = serde_yaml::from_slice(content)?;
+    Ok(yaml_to_json(value))
+}
+
+fn yaml_to_json(value: serde_yaml::Value) -> Value {
+    use serde_yaml::Value as Yaml;
+    match value {
+        Yaml::Null => Value::Null,
+        Yaml::Bool(b) => Value::Bool(b),
+        Yaml::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::Number(i.into())
+            } else if let Some(u) = n.as_u64() {
+                Value::Number(u.into())
+            } else {
+                n.as_f64().and_then(Number::from_f64).map(Value::Number).unwrap_or(Value::Null)
+            }
+        }
+        Yaml::String(s) => Value::String(s),
+        Yaml::Sequence(seq) => Value::Array(seq.into_iter().map(yaml_to_json).collect()),
+        Yaml::Mapping(mapping) => Value::Object(
+            mapping.into_iter()
+                .map(|(k, v)| (yaml_key(k), yaml_to_json(v)))
+                .collect::<Map<_, _>>()
+        ),
+        Yaml::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+fn yaml_key(key: serde_yaml::Value) -> String {
+    match yaml_to_json(key) {
+        Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use serde_json::json;
+    use crate::{Bytes, Spec};
+
+    fn spec(name: &'static str, content: &'static str) -> Spec {
+        Spec {
+            name: Cow::Borrowed(name),
+            content: Bytes::from_static(content.as_bytes()),
+        }
+    }
+
+    #[test]
+    fn parses_yaml_with_integer_keys() {
+        let value = spec("openapi.yaml", "responses:\n  200:\n    description: ok\n").parse().unwrap();
+        assert_eq!(value, json!({"responses": {"200": {"description": "ok"}}}));
+    }
+
+    #[test]
+    fn reports_error_position() {
+        let err = spec("openapi.json", "{\n  \"openapi\": \"3.0.0\",\n  oops\n}").parse().unwrap_err();
+        assert_eq!((err.line, err.column), (Some(3), Some(3)));
+        assert!(!err.message.contains(" at line "));
+    }
+}
@@ -0,0 +1,147 @@
+use std::borrow::Cow;
+use std::fmt;
+
+/// A swagger-ui plugin or preset written in JavaScript.
+///
+/// The adapters serve it as `plugins/<name>.js` (or `presets/<name>.js`)
+/// and the generated index page passes it to `SwaggerUIBundle`.
+#[derive(Debug, Clone)]
+pub struct Plugin {
+    name: Cow<'static, str>,
+    source: Cow<'static, str>,
+}
+
+/// Error returned by [`Plugin::new`] for a name which can't be used in a file name
+#[derive(Debug)]
+pub struct InvalidPluginName {
+    /// The rejected name
+    pub name: String,
+}
+
+impl fmt::Display for InvalidPluginName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid plugin name {:?}, it may only contain ASCII alphanumerics, `-` and `_`", self.name)
+    }
+}
+
+impl std::error::Error for InvalidPluginName {}
+
+impl Plugin {
+    /// Create a new `Plugin` from the provided name and source.
+    /// `source` is a JavaScript expression evaluating to the plugin, e.g.
+    /// `function(system) { return { wrapComponents: { ... } } }`
+    ///
+    /// Fails if the name is empty or contains characters other than ASCII alphanumerics, `-` and `_`.
+    pub fn new(name: impl Into<Cow<'static, str>>, source: impl Into<Cow<'static, str>>) -> Result<Self, InvalidPluginName> {
+        let name = name.into();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(InvalidPluginName { name: name.into_owned() });
+        }
+        Ok(Self {
+            name,
+            source: source.into(),
+        })
+    }
+
+    /// Name of the plugin, used for its file name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// JavaScript source of the plugin
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Script registering the plugin in the `registry` array on `window`
+    pub(crate) fn script(&self, registry: &str) -> String {
+        format!(
+            "(window.{0} = window.{0} || []).push(\n{1}\n);\n",
+            registry, self.source
+        )
+    }
+}
+
+/// A `requestInterceptor` or `responseInterceptor` function written in JavaScript.
+///
+/// Interceptors are chained in the order they were added; each one receives the value
+/// returned by the previous one, which may also be a `Promise`.
+#[derive(Debug, Clone)]
+pub struct Interceptor {
+    /// JavaScript expression evaluating to the function, e.g.
+    /// `function(request) { request.headers["X-Foo"] = "bar"; return request; }`
+    pub source: Cow<'static, str>,
+}
+
+impl Interceptor {
+    /// Create a new `Interceptor` from the provided source
+    pub fn new(source: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            source: source.into(),
+        }
+    }
+}
+
+/// Name of the window property where plugins are registered
+pub(crate) const PLUGINS: &str = "swaggerUiPlugins";
+/// Name of the window property where presets are registered
+pub(crate) const PRESETS: &str = "swaggerUiPresets";
+
+/// Helper used by the generated initializer to chain interceptors
+pub(crate) const CHAIN_FN: &str = r#"function chain(interceptors) {
+        return function(value) {
+          return interceptors.reduce(function(value, interceptor) {
+            return value && typeof value.then === "function" ? value.then(interceptor) : interceptor(value);
+          }, value);
+        };
+      }"#;
+
+#[cfg(test)]
+mod tests {
+    use crate::{Interceptor, Options, Plugin};
+
+    fn options() -> Options {
+        Options {
+            plugins: vec![Plugin::new("hide-info", "function() { return { components: { info: function() { return null; } } }; }").unwrap()],
+            presets: vec![Plugin::new("my_preset", "function() { return {}; }").unwrap()],
+            request_interceptors: vec![
+                Interceptor::new("function(req) { req.headers['X-A'] = '1'; return req; }"),
+                Interceptor::new("function(req) { return Promise.resolve(req); }"),
+            ],
+            response_interceptors: vec![Interceptor::new("function(res) { console.log('</script>'); return res; }")],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plugins_are_served() {
+        let options = options();
+        let plugin = String::from_utf8(options.file("plugins/hide-info.js").unwrap().to_vec()).unwrap();
+        assert!(plugin.starts_with("(window.swaggerUiPlugins = window.swaggerUiPlugins || []).push(\nfunction() {"));
+        assert!(options.file("presets/my_preset.js").is_some());
+        assert!(options.file("plugins/missing.js").is_none());
+    }
+
+    #[test]
+    fn plugins_are_loaded() {
+        let index = String::from_utf8(options().file("index.html").unwrap().to_vec()).unwrap();
+        assert!(index.contains(r#"<script src="./plugins/hide-info.js" charset="UTF-8"></script>"#));
+        assert!(index.contains(r#"<script src="./presets/my_preset.js" charset="UTF-8"></script>"#));
+        assert!(index.contains("plugins: [SwaggerUIBundle.plugins.DownloadUrl].concat(window.swaggerUiPlugins || [])"));
+        assert!(index.contains("requestInterceptor: chain([\n"));
+        assert!(index.contains("function(res) { console.log('<\\/script>'); return res; }"));
+    }
+
+    #[test]
+    fn no_interceptors_by_default() {
+        let index = String::from_utf8(Options::default().file("index.html").unwrap().to_vec()).unwrap();
+        assert!(!index.contains("Interceptor"));
+    }
+
+    #[test]
+    fn rejects_invalid_names() {
+        for name in ["../evil", ""] {
+            assert_eq!(Plugin::new(name, "").unwrap_err().name, name);
+        }
+    }
+}
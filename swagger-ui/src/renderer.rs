@@ -0,0 +1,172 @@
+//! Alternative spec renderers which can be mounted next to swagger-ui.
+//!
+//! Bundled renderers are enabled with `redoc`, `rapidoc` and `scalar` features.
+//! Adapters serve any `Renderer` over the same `Spec`/`SpecOrUrl` as swagger-ui,
+//! with the default `SecurityHeaders`.
+
+use crate::index::escape_html;
+#[cfg(any(feature = "redoc", feature = "rapidoc", feature = "scalar"))]
+use crate::overlay::cow_to_bytes;
+use crate::{Bytes, FileResponse, SecurityHeaders};
+#[cfg(any(feature = "redoc", feature = "rapidoc", feature = "scalar"))]
+use rust_embed::RustEmbed;
+
+/// A web page rendering a spec
+pub trait Renderer: Send + Sync + 'static {
+    /// Short name of the renderer, e.g. `"redoc"`
+    fn name(&self) -> &'static str;
+
+    /// Returns content of a file served under the renderer mount:
+    /// `index.html` rendering the spec at `spec_url`, or one of renderer assets.
+    fn file(&self, path: &str, spec_url: &str) -> Option<Bytes>;
+
+    /// Same as `file`, along with `SecurityHeaders::relaxed`, since renderer pages have inline scripts
+    fn file_response(&self, path: &str, spec_url: &str) -> Option<FileResponse> {
+        let path = path.trim_start_matches('/');
+        self.file(path, spec_url).map(|content| FileResponse {
+            content,
+            headers: SecurityHeaders::relaxed().headers(path, None),
+        })
+    }
+}
+
+/// Generates a minimal html page with the specified body
+pub fn page(title: &str, body: &str) -> String {
+    format!(r#"<!DOCTYPE html>
+<html lang="en">
+  <head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <title>{}</title>
+    <style>body {{ margin: 0; padding: 0; }}</style>
+  </head>
+  <body>
+    {}
+  </body>
+</html>
+"#, escape_html(title), body)
+}
+
+/// [Redoc](https://github.com/Redocly/redoc) renderer
+#[cfg(feature = "redoc")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Redoc;
+
+/// Assets from redoc standalone bundle
+#[cfg(feature = "redoc")]
+#[derive(RustEmbed)]
+#[folder = "$CARGO_MANIFEST_DIR/.redoc"]
+pub struct RedocAssets;
+
+#[cfg(feature = "redoc")]
+impl Renderer for Redoc {
+    fn name(&self) -> &'static str {
+        "redoc"
+    }
+
+    fn file(&self, path: &str, spec_url: &str) -> Option<Bytes> {
+        match path.trim_start_matches('/') {
+            "index.html" => Some(Bytes::from(page("API Reference", &format!(
+                concat!(
+                    r#"<redoc spec-url="{}"></redoc>"#, "\n    ",
+                    r#"<script src="./redoc.standalone.js" charset="UTF-8"></script>"#,
+                ),
+                escape_html(spec_url)
+            )))),
+            path => RedocAssets::get(path).map(cow_to_bytes),
+        }
+    }
+}
+
+/// [RapiDoc](https://rapidocweb.com) renderer
+#[cfg(feature = "rapidoc")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RapiDoc;
+
+/// Assets from rapidoc dist
+#[cfg(feature = "rapidoc")]
+#[derive(RustEmbed)]
+#[folder = "$CARGO_MANIFEST_DIR/.rapidoc"]
+pub struct RapiDocAssets;
+
+#[cfg(feature = "rapidoc")]
+impl Renderer for RapiDoc {
+    fn name(&self) -> &'static str {
+        "rapidoc"
+    }
+
+    fn file(&self, path: &str, spec_url: &str) -> Option<Bytes> {
+        match path.trim_start_matches('/') {
+            "index.html" => Some(Bytes::from(page("API Reference", &format!(
+                concat!(
+                    r#"<rapi-doc spec-url="{}"></rapi-doc>"#, "\n    ",
+                    r#"<script type="module" src="./rapidoc-min.js"></script>"#,
+                ),
+                escape_html(spec_url)
+            )))),
+            path => RapiDocAssets::get(path).map(cow_to_bytes),
+        }
+    }
+}
+
+/// [Scalar](https://github.com/scalar/scalar) API reference renderer
+#[cfg(feature = "scalar")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Scalar;
+
+/// Assets from scalar api-reference standalone bundle
+#[cfg(feature = "scalar")]
+#[derive(RustEmbed)]
+#[folder = "$CARGO_MANIFEST_DIR/.scalar"]
+pub struct ScalarAssets;
+
+#[cfg(feature = "scalar")]
+impl Renderer for Scalar {
+    fn name(&self) -> &'static str {
+        "scalar"
+    }
+
+    fn file(&self, path: &str, spec_url: &str) -> Option<Bytes> {
+        match path.trim_start_matches('/') {
+            "index.html" => Some(Bytes::from(page("API Reference", &format!(
+                concat!(
+                    r#"<script id="api-reference" data-url="{}"></script>"#, "\n    ",
+                    r#"<script src="./scalar.standalone.js" charset="UTF-8"></script>"#,
+                ),
+                escape_html(spec_url)
+            )))),
+            path => ScalarAssets::get(path).map(cow_to_bytes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(feature = "redoc")]
+    #[test]
+    fn redoc_files() {
+        let index = Redoc.file("index.html", "./openapi.json").unwrap();
+        assert!(std::str::from_utf8(&index).unwrap().contains(r#"<redoc spec-url="./openapi.json"></redoc>"#));
+        assert!(!Redoc.file("/redoc.standalone.js", "./openapi.json").unwrap().is_empty());
+        assert!(Redoc.file("swagger-ui.css", "./openapi.json").is_none());
+    }
+
+    #[cfg(feature = "rapidoc")]
+    #[test]
+    fn rapidoc_files() {
+        let index = RapiDoc.file("index.html", "./openapi.json").unwrap();
+        assert!(std::str::from_utf8(&index).unwrap().contains(r#"<rapi-doc spec-url="./openapi.json"></rapi-doc>"#));
+        assert!(!RapiDoc.file("rapidoc-min.js", "./openapi.json").unwrap().is_empty());
+    }
+
+    #[cfg(feature = "scalar")]
+    #[test]
+    fn scalar_files() {
+        let index = Scalar.file("index.html", "https://example.com/openapi.json?a=1&b=2").unwrap();
+        assert!(std::str::from_utf8(&index).unwrap().contains(r#"data-url="https://example.com/openapi.json?a=1&amp;b=2""#));
+        assert!(!Scalar.file("scalar.standalone.js", "./openapi.json").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,132 @@
+use std::borrow::Cow;
+use crate::index::js_string;
+use crate::Interceptor;
+
+/// A header added to the requests made by swagger-ui, e.g. "Try it out" calls.
+///
+/// Headers are only added to requests to the origin of the swagger-ui page, and to `origins`,
+/// so tokens aren't sent to third-party servers or external spec urls.
+#[derive(Debug, Clone)]
+pub struct RequestHeader {
+    /// Header name
+    pub name: Cow<'static, str>,
+    /// Where the header value comes from
+    pub value: HeaderSource,
+    /// Other origins the header is added to, e.g. `https://api.example.com`
+    pub origins: Vec<Cow<'static, str>>,
+}
+
+/// Source of a `RequestHeader` value.
+/// Headers whose cookie or storage entry is missing are not sent.
+#[derive(Debug, Clone)]
+pub enum HeaderSource {
+    /// Fixed value
+    Static(Cow<'static, str>),
+    /// Value of the cookie with this name
+    Cookie(Cow<'static, str>),
+    /// Value of the `localStorage` entry with this key
+    LocalStorage(Cow<'static, str>),
+    /// Value of the `sessionStorage` entry with this key
+    SessionStorage(Cow<'static, str>),
+}
+
+impl RequestHeader {
+    /// Header with a fixed value
+    pub fn fixed(name: impl Into<Cow<'static, str>>, value: impl Into<Cow<'static, str>>) -> Self {
+        Self { name: name.into(), value: HeaderSource::Static(value.into()), origins: vec![] }
+    }
+
+    /// Header copied from a cookie
+    pub fn cookie(name: impl Into<Cow<'static, str>>, cookie: impl Into<Cow<'static, str>>) -> Self {
+        Self { name: name.into(), value: HeaderSource::Cookie(cookie.into()), origins: vec![] }
+    }
+
+    /// Header copied from `localStorage`
+    pub fn local_storage(name: impl Into<Cow<'static, str>>, key: impl Into<Cow<'static, str>>) -> Self {
+        Self { name: name.into(), value: HeaderSource::LocalStorage(key.into()), origins: vec![] }
+    }
+
+    /// Header copied from `sessionStorage`
+    pub fn session_storage(name: impl Into<Cow<'static, str>>, key: impl Into<Cow<'static, str>>) -> Self {
+        Self { name: name.into(), value: HeaderSource::SessionStorage(key.into()), origins: vec![] }
+    }
+
+    /// Also adds the header to requests to `origin`, e.g. `https://api.example.com`
+    pub fn with_origin(mut self, origin: impl Into<Cow<'static, str>>) -> Self {
+        let origin = origin.into();
+        self.origins.push(match origin.strip_suffix('/') {
+            Some(origin) => Cow::Owned(origin.to_owned()),
+            None => origin,
+        });
+        self
+    }
+
+    fn value_expression(&self) -> String {
+        match &self.value {
+            HeaderSource::Static(value) => js_string(value),
+            HeaderSource::Cookie(cookie) => format!("cookie({})", js_string(cookie)),
+            HeaderSource::LocalStorage(key) => format!("window.localStorage.getItem({})", js_string(key)),
+            HeaderSource::SessionStorage(key) => format!("window.sessionStorage.getItem({})", js_string(key)),
+        }
+    }
+}
+
+/// Compiles headers into a request interceptor
+pub(crate) fn interceptor(headers: &[RequestHeader]) -> Interceptor {
+    let headers = headers.iter()
+        .map(|h| {
+            let origins = h.origins.iter().map(|origin| js_string(origin)).collect::<Vec<_>>().join(", ");
+            format!("[{}, {}, [{}]]", js_string(&h.name), h.value_expression(), origins)
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    Interceptor::new(format!(r#"function(request) {{
+              function cookie(name) {{
+                var prefix = name + "=";
+                var found = document.cookie.split("; ").filter(function(c) {{ return c.indexOf(prefix) === 0; }})[0];
+                return found === undefined ? null : decodeURIComponent(found.substring(prefix.length));
+              }}
+              var origin = new URL(request.url, window.location.href).origin;
+              [{}].forEach(function(header) {{
+                var allowed = origin === window.location.origin || header[2].indexOf(origin) !== -1;
+                if (allowed && header[1] !== null && header[1] !== undefined) request.headers[header[0]] = header[1];
+              }});
+              return request;
+            }}"#, headers))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Interceptor, Options, RequestHeader};
+
+    #[test]
+    fn headers_are_injected_first() {
+        let options = Options {
+            request_headers: vec![
+                RequestHeader::fixed("X-Client", "docs \"</script>\""),
+                RequestHeader::cookie("X-CSRF-Token", "csrftoken"),
+                RequestHeader::local_storage("X-Tenant", "tenant").with_origin("https://api.example.com/"),
+            ],
+            request_interceptors: vec![Interceptor::new("function(request) { return request; }")],
+            ..Default::default()
+        };
+        let interceptors = options.request_interceptors();
+        assert_eq!(interceptors.len(), 2);
+        assert!(interceptors[0].source.contains(concat!(
+            r#"[["X-Client", "docs \"\u003c/script>\"", []], "#,
+            r#"["X-CSRF-Token", cookie("csrftoken"), []], "#,
+            r#"["X-Tenant", window.localStorage.getItem("tenant"), ["https://api.example.com"]]]"#,
+        )));
+        // Requests to other origins only get the headers allowing them
+        assert!(interceptors[0].source.contains("var origin = new URL(request.url, window.location.href).origin;"));
+        assert!(interceptors[0].source.contains("origin === window.location.origin || header[2].indexOf(origin) !== -1"));
+
+        let index = String::from_utf8(options.file("index.html").unwrap().to_vec()).unwrap();
+        assert!(index.contains(r#"cookie("csrftoken")"#));
+    }
+
+    #[test]
+    fn no_headers_no_interceptor() {
+        assert!(Options::default().request_interceptors().is_empty());
+    }
+}
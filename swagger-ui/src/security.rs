@@ -0,0 +1,167 @@
+use std::borrow::Cow;
+
+/// Security headers sent by the adapters with the files of the swagger-ui mount.
+///
+/// The default sends `DEFAULT_CSP` with `frame-ancestors 'self'`, `X-Content-Type-Options: nosniff`
+/// and `Referrer-Policy: no-referrer`. `relaxed` drops the policy but keeps `frame-ancestors`.
+#[derive(Debug, Clone)]
+pub struct SecurityHeaders {
+    /// `Content-Security-Policy` of html pages, without `frame-ancestors`.
+    /// `{nonce}` is replaced with the nonce of the generated `index.html` initializer script.
+    /// Default: `DEFAULT_CSP`.
+    pub content_security_policy: Option<Cow<'static, str>>,
+    /// `frame-ancestors` directive added to the policy.
+    /// Default: `'self'`.
+    pub frame_ancestors: Option<Cow<'static, str>>,
+    /// Sends `X-Content-Type-Options: nosniff`.
+    /// Default: `true`.
+    pub nosniff: bool,
+    /// `Referrer-Policy` header.
+    /// Default: `no-referrer`.
+    pub referrer_policy: Option<Cow<'static, str>>,
+}
+
+/// `Content-Security-Policy` of the default `SecurityHeaders`.
+///
+/// swagger-ui sets inline styles, shows validator badges and images from the spec over https,
+/// and sends "Try it out" requests to the same origin.
+/// Add hosts to `connect-src` to load specs or try out APIs served elsewhere, and for OAuth2 token requests.
+pub const DEFAULT_CSP: &str = concat!(
+    "default-src 'self'; ",
+    "script-src 'self' 'nonce-{nonce}'; ",
+    "style-src 'self' 'unsafe-inline'; ",
+    "img-src 'self' data: https:; ",
+    "font-src 'self' data:; ",
+    "connect-src 'self'; ",
+    "object-src 'none'; ",
+    "base-uri 'self'; ",
+    "form-action 'self'",
+);
+
+impl Default for SecurityHeaders {
+    fn default() -> Self {
+        Self {
+            content_security_policy: Some(Cow::Borrowed(DEFAULT_CSP)),
+            frame_ancestors: Some(Cow::Borrowed("'self'")),
+            nosniff: true,
+            referrer_policy: Some(Cow::Borrowed("no-referrer")),
+        }
+    }
+}
+
+impl SecurityHeaders {
+    /// Default headers without `DEFAULT_CSP`: pages can load and reach anything,
+    /// but can still only be framed by the same origin.
+    /// Used for the renderer and editor pages, whose inline scripts have no nonce.
+    pub fn relaxed() -> Self {
+        Self {
+            content_security_policy: None,
+            ..Self::default()
+        }
+    }
+
+    /// No security headers, leaving them to the host application
+    pub fn none() -> Self {
+        Self {
+            content_security_policy: None,
+            frame_ancestors: None,
+            nosniff: false,
+            referrer_policy: None,
+        }
+    }
+
+    /// Returns `true` if html pages need a nonce for their inline scripts
+    pub(crate) fn uses_nonce(&self) -> bool {
+        self.content_security_policy.as_deref().is_some_and(|csp| csp.contains("{nonce}"))
+    }
+
+    /// Headers for the file at `path`; `nonce` is the one used by the page, if it's html
+    pub fn headers(&self, path: &str, nonce: Option<&str>) -> Vec<(&'static str, String)> {
+        let mut headers = vec![];
+        if path.ends_with(".html") {
+            let mut directives = vec![];
+            if let Some(csp) = &self.content_security_policy {
+                // Without a nonce, 'nonce-' is left out so inline scripts stay blocked
+                let csp = match nonce {
+                    Some(nonce) => csp.replace("{nonce}", nonce),
+                    None => csp.replace(" 'nonce-{nonce}'", "").replace("{nonce}", ""),
+                };
+                directives.push(csp);
+            }
+            if let Some(frame_ancestors) = &self.frame_ancestors {
+                directives.push(format!("frame-ancestors {}", frame_ancestors));
+            }
+            if !directives.is_empty() {
+                headers.push(("Content-Security-Policy", directives.join("; ")));
+            }
+        }
+        if self.nosniff {
+            headers.push(("X-Content-Type-Options", "nosniff".to_owned()));
+        }
+        if let Some(referrer_policy) = &self.referrer_policy {
+            headers.push(("Referrer-Policy", referrer_policy.to_string()));
+        }
+        headers
+    }
+}
+
+/// Generates a random nonce for a `Content-Security-Policy`
+pub(crate) fn nonce() -> String {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).expect("failed to generate a nonce");
+    crate::index::base64(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Options, SecurityHeaders};
+
+    fn header<'a>(headers: &'a [(&'static str, String)], name: &str) -> Option<&'a str> {
+        headers.iter().find(|(n, _)| *n == name).map(|(_, v)| v.as_str())
+    }
+
+    #[test]
+    fn index_gets_a_nonce() {
+        let options = Options::default();
+        let first = options.file_response("index.html").unwrap();
+        let second = options.file_response("index.html").unwrap();
+
+        let csp = header(&first.headers, "Content-Security-Policy").unwrap();
+        let nonce = csp.split("'nonce-").nth(1).unwrap().split('\'').next().unwrap();
+        assert!(csp.ends_with("; frame-ancestors 'self'"));
+        assert!(std::str::from_utf8(&first.content).unwrap().contains(&format!(r#"<script nonce="{}">"#, nonce)));
+        assert_ne!(Some(csp), header(&second.headers, "Content-Security-Policy"));
+        assert_eq!(header(&first.headers, "X-Content-Type-Options"), Some("nosniff"));
+        assert_eq!(header(&first.headers, "Referrer-Policy"), Some("no-referrer"));
+    }
+
+    #[test]
+    fn assets_get_no_csp() {
+        let response = Options::default().file_response("swagger-ui-bundle.js").unwrap();
+        assert_eq!(header(&response.headers, "Content-Security-Policy"), None);
+        assert_eq!(header(&response.headers, "X-Content-Type-Options"), Some("nosniff"));
+    }
+
+    #[test]
+    fn csp_can_be_relaxed() {
+        let options = Options {
+            security_headers: SecurityHeaders::relaxed(),
+            ..Default::default()
+        };
+        let response = options.file_response("index.html").unwrap();
+        assert_eq!(header(&response.headers, "Content-Security-Policy"), Some("frame-ancestors 'self'"));
+        assert_eq!(header(&response.headers, "X-Content-Type-Options"), Some("nosniff"));
+        assert!(std::str::from_utf8(&response.content).unwrap().contains("<script>"));
+    }
+
+    #[test]
+    fn headers_can_be_disabled() {
+        let options = Options {
+            security_headers: SecurityHeaders::none(),
+            ..Default::default()
+        };
+        let response = options.file_response("index.html").unwrap();
+        assert!(response.headers.is_empty());
+        assert!(std::str::from_utf8(&response.content).unwrap().contains("<script>"));
+    }
+}
@@ -0,0 +1,220 @@
+use std::borrow::Cow;
+use crate::Bytes;
+
+/// Branding options applied to the generated index page.
+///
+/// `color_scheme` depends on the `dark-theme` feature, so it's non exhaustive:
+/// start from `Theme::default()` and set its fields.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct Theme {
+    /// Page title.
+    /// Default: `"Swagger UI"`.
+    pub title: Option<Cow<'static, str>>,
+    /// Custom CSS, applied after swagger-ui styles.
+    /// Use `include_str!` or `std::fs::read_to_string` to load it from a file.
+    pub css: Option<Cow<'static, str>>,
+    /// Logo shown in the top bar instead of the swagger-ui one.
+    pub logo: Option<Image>,
+    /// Page favicon.
+    /// Default: swagger-ui favicons.
+    pub favicon: Option<Image>,
+    /// Hides the top bar, including the spec selector shown when `Config::urls` is set.
+    /// Default: `false`.
+    pub hide_top_bar: bool,
+    /// Selects between light and bundled dark styles.
+    /// It's part of the theme rather than `Config`: swagger-ui has no dark mode, the styles are
+    /// added to the generated `index.css` by the server.
+    /// Default: `ColorScheme::Light`.
+    #[cfg(feature = "dark-theme")]
+    pub color_scheme: ColorScheme,
+}
+
+/// Color scheme of the page, requires `dark-theme` feature
+#[cfg(feature = "dark-theme")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorScheme {
+    /// Default swagger-ui styles
+    #[default]
+    Light,
+    /// Bundled dark styles
+    Dark,
+    /// Follow `prefers-color-scheme` of the browser
+    System,
+}
+
+/// Dark styles, applied on top of `swagger-ui.css`
+#[cfg(feature = "dark-theme")]
+const DARK_CSS: &str = include_str!("dark.css");
+
+/// An image used by `Theme`, either served from elsewhere or by the adapters
+#[derive(Debug, Clone)]
+pub enum Image {
+    /// Image url, relative to the swagger-ui mount or absolute
+    Url(Cow<'static, str>),
+    /// Image file served by the adapters under the swagger-ui mount
+    File {
+        /// File name, also used to guess its content type
+        name: Cow<'static, str>,
+        /// File content
+        content: Bytes,
+    },
+}
+
+impl Image {
+    /// Url of the image, relative to the swagger-ui mount
+    pub fn url(&self) -> Cow<'_, str> {
+        match self {
+            Image::Url(url) => Cow::Borrowed(url),
+            Image::File { name, .. } => Cow::Owned(format!("./{}", name.trim_start_matches('/'))),
+        }
+    }
+
+    /// Url of the image, with files encoded as `data:` urls
+    pub(crate) fn data_url(&self) -> Cow<'_, str> {
+        match self {
+            Image::Url(url) => Cow::Borrowed(url),
+            Image::File { name, content } => Cow::Owned(crate::index::data_url(name, content)),
+        }
+    }
+
+    /// Returns the image content if it's a file served at `path`
+    pub(crate) fn file(&self, path: &str) -> Option<Bytes> {
+        match self {
+            Image::File { name, content } if name.trim_start_matches('/') == path => Some(content.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl Theme {
+    /// Returns content of the files served for this theme, e.g. the logo
+    pub(crate) fn file(&self, path: &str) -> Option<Bytes> {
+        self.logo.iter()
+            .chain(self.favicon.iter())
+            .find_map(|image| image.file(path))
+    }
+
+    /// Paths of the files served for this theme
+    pub(crate) fn paths(&self) -> impl Iterator<Item = String> + '_ {
+        self.logo.iter()
+            .chain(self.favicon.iter())
+            .filter_map(|image| match image {
+                Image::File { name, .. } => Some(name.trim_start_matches('/').to_owned()),
+                Image::Url(_) => None,
+            })
+    }
+
+    /// Stylesheet with the theme rules, served as `index.css`.
+    /// With `inline_images`, image files are encoded as `data:` urls.
+    pub(crate) fn stylesheet(&self, inline_images: bool) -> String {
+        // Same as `index.css` from swagger-ui-dist
+        let mut css = String::from(concat!(
+            "html { box-sizing: border-box; overflow: -moz-scrollbars-vertical; overflow-y: scroll; }\n",
+            "*, *:before, *:after { box-sizing: inherit; }\n",
+            "body { margin: 0; background: #fafafa; }\n",
+        ));
+        #[cfg(feature = "dark-theme")]
+        match self.color_scheme {
+            ColorScheme::Light => {}
+            ColorScheme::Dark => css.push_str(DARK_CSS),
+            ColorScheme::System => {
+                css.push_str("@media (prefers-color-scheme: dark) {\n");
+                css.push_str(DARK_CSS);
+                css.push_str("}\n");
+            }
+        }
+        if self.hide_top_bar {
+            css.push_str(".swagger-ui .topbar { display: none; }\n");
+        }
+        if let Some(logo) = &self.logo {
+            css.push_str(".swagger-ui .topbar .topbar-wrapper .link svg, .swagger-ui .topbar .topbar-wrapper .link img { display: none; }\n");
+            let url = if inline_images { logo.data_url() } else { logo.url() };
+            css.push_str(&format!(".swagger-ui .topbar .topbar-wrapper .link::before {{ content: url({}); }}\n", css_string(&url)));
+        }
+        if let Some(custom) = &self.css {
+            css.push_str(custom);
+            css.push('\n');
+        }
+        css
+    }
+}
+
+fn css_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\a "),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use crate::{Bytes, Image, Options, Theme};
+
+    // `color_scheme` is only there with `dark-theme` feature
+    #[cfg_attr(not(feature = "dark-theme"), allow(clippy::needless_update))]
+    fn options() -> Options {
+        Options {
+            theme: Theme {
+                title: Some(Cow::Borrowed("Pets & <Co>")),
+                css: Some(Cow::Borrowed(".swagger-ui { color: red; }")),
+                logo: Some(Image::File { name: Cow::Borrowed("logo.svg"), content: Bytes::from_static(b"<svg/>") }),
+                favicon: Some(Image::Url(Cow::Borrowed("https://example.com/favicon.ico"))),
+                hide_top_bar: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn index_is_branded() {
+        let index = String::from_utf8(options().file("index.html").unwrap().to_vec()).unwrap();
+        assert!(index.contains("<title>Pets &amp; &lt;Co&gt;</title>"));
+        assert!(index.contains(r#"<link rel="icon" href="https://example.com/favicon.ico" />"#));
+        assert!(!index.contains("favicon-32x32.png"));
+    }
+
+    #[test]
+    fn theme_files_are_served() {
+        let options = options();
+        let css = String::from_utf8(options.file("index.css").unwrap().to_vec()).unwrap();
+        assert!(css.contains(".swagger-ui .topbar { display: none; }"));
+        assert!(css.contains(r#"content: url("./logo.svg");"#));
+        assert!(css.ends_with(".swagger-ui { color: red; }\n"));
+        assert_eq!(options.file("logo.svg").unwrap(), "<svg/>");
+    }
+
+    #[cfg(feature = "dark-theme")]
+    #[test]
+    fn dark_theme() {
+        use crate::ColorScheme;
+
+        let css = |color_scheme| Options {
+            theme: Theme { color_scheme, ..Default::default() },
+            ..Default::default()
+        }.theme.stylesheet(false);
+
+        assert!(!css(ColorScheme::Light).contains(super::DARK_CSS));
+        assert!(css(ColorScheme::Dark).contains(super::DARK_CSS));
+        assert!(css(ColorScheme::System).contains(&format!("@media (prefers-color-scheme: dark) {{\n{}}}", super::DARK_CSS)));
+    }
+
+    #[test]
+    fn default_index() {
+        let index = String::from_utf8(Options::default().file("index.html").unwrap().to_vec()).unwrap();
+        assert!(index.contains("<title>Swagger UI</title>"));
+        assert!(index.contains(r#"configUrl: "./swagger-ui-config.json""#));
+    }
+}
@@ -0,0 +1,275 @@
+//! Structural validation of OpenAPI documents, enabled with the `validate` feature.
+//!
+//! This is not a full schema validation: it checks that a document looks like
+//! OpenAPI 2.0, 3.0 or 3.1 (version marker, required fields) and that all local `$ref`s
+//! resolve, which catches most mistakes that would otherwise only show up in the browser.
+
+use std::fmt;
+use serde_json::Value;
+use crate::{ParseError, Spec};
+
+/// OpenAPI version detected by the validator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecVersion {
+    /// Swagger 2.0
+    Swagger2,
+    /// OpenAPI 3.0.x
+    OpenApi30,
+    /// OpenAPI 3.1.x
+    OpenApi31,
+}
+
+/// A single problem found in a spec
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// JSON pointer to the offending value, empty for the document root
+    pub pointer: String,
+    /// What is wrong with the value
+    pub kind: ValidationErrorKind,
+}
+
+/// Kind of a `ValidationError`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationErrorKind {
+    /// Content is neither valid JSON nor valid YAML
+    Parse(ParseError),
+    /// Neither `swagger: "2.0"` nor `openapi: "3.0.x"`/`"3.1.x"` is present
+    UnknownVersion(Option<String>),
+    /// A required field is missing
+    MissingField(&'static str),
+    /// Value has a wrong type
+    InvalidType { expected: &'static str },
+    /// Path key does not start with `/`
+    InvalidPath(String),
+    /// Local `$ref` does not point to an existing value
+    UnresolvedRef(String),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pointer = if self.pointer.is_empty() { "/" } else { &self.pointer };
+        match &self.kind {
+            ValidationErrorKind::Parse(err) => write!(f, "failed to parse spec: {}", err),
+            ValidationErrorKind::UnknownVersion(Some(version)) => write!(f, "{}: unsupported version {:?}", pointer, version),
+            ValidationErrorKind::UnknownVersion(None) => write!(f, "{}: missing `swagger` or `openapi` version field", pointer),
+            ValidationErrorKind::MissingField(field) => write!(f, "{}: missing required field `{}`", pointer, field),
+            ValidationErrorKind::InvalidType { expected } => write!(f, "{}: expected {}", pointer, expected),
+            ValidationErrorKind::InvalidPath(path) => write!(f, "{}: path {:?} must start with `/`", pointer, path),
+            ValidationErrorKind::UnresolvedRef(reference) => write!(f, "{}: unresolved reference {:?}", pointer, reference),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+const METHODS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+impl Spec {
+    /// Validates the spec, see [`validate`](crate::validate::validate)
+    pub fn validate(&self) -> Result<SpecVersion, Vec<ValidationError>> {
+        validate(self)
+    }
+}
+
+/// Parses and validates the spec, returning its version or every problem found
+pub fn validate(spec: &Spec) -> Result<SpecVersion, Vec<ValidationError>> {
+    match spec.parse() {
+        Ok(document) => validate_value(&document),
+        Err(err) => Err(vec![ValidationError { pointer: String::new(), kind: ValidationErrorKind::Parse(err) }]),
+    }
+}
+
+/// Validates an already parsed document
+pub fn validate_value(document: &Value) -> Result<SpecVersion, Vec<ValidationError>> {
+    let mut errors = Errors::default();
+
+    let root = match document.as_object() {
+        Some(root) => root,
+        None => {
+            errors.push("", ValidationErrorKind::InvalidType { expected: "an object" });
+            return Err(errors.0);
+        }
+    };
+
+    let version = match (root.get("swagger"), root.get("openapi")) {
+        (Some(Value::String(v)), _) if v == "2.0" => SpecVersion::Swagger2,
+        (_, Some(Value::String(v))) if v.starts_with("3.0.") => SpecVersion::OpenApi30,
+        (_, Some(Value::String(v))) if v.starts_with("3.1.") => SpecVersion::OpenApi31,
+        (Some(v), _) | (_, Some(v)) => {
+            errors.push("", ValidationErrorKind::UnknownVersion(Some(v.as_str().map(str::to_owned).unwrap_or_else(|| v.to_string()))));
+            return Err(errors.0);
+        }
+        (None, None) => {
+            errors.push("", ValidationErrorKind::UnknownVersion(None));
+            return Err(errors.0);
+        }
+    };
+
+    match root.get("info") {
+        Some(Value::Object(info)) => {
+            for field in ["title", "version"] {
+                match info.get(field) {
+                    Some(Value::String(_)) => {}
+                    Some(_) => errors.push(format!("/info/{}", field), ValidationErrorKind::InvalidType { expected: "a string" }),
+                    None => errors.push("/info", ValidationErrorKind::MissingField(field)),
+                }
+            }
+        }
+        Some(_) => errors.push("/info", ValidationErrorKind::InvalidType { expected: "an object" }),
+        None => errors.push("", ValidationErrorKind::MissingField("info")),
+    }
+
+    match root.get("paths") {
+        Some(Value::Object(paths)) => {
+            for (path, item) in paths {
+                let pointer = format!("/paths/{}", escape(path));
+                if !path.starts_with('/') && !path.starts_with("x-") {
+                    errors.push(pointer.clone(), ValidationErrorKind::InvalidPath(path.clone()));
+                }
+                if let Some(item) = item.as_object() {
+                    for method in METHODS {
+                        match item.get(method) {
+                            // `responses` became optional in 3.1
+                            Some(Value::Object(operation)) if version != SpecVersion::OpenApi31 && !operation.contains_key("responses") => {
+                                errors.push(format!("{}/{}", pointer, method), ValidationErrorKind::MissingField("responses"));
+                            }
+                            Some(Value::Object(_)) => {}
+                            Some(_) => errors.push(format!("{}/{}", pointer, method), ValidationErrorKind::InvalidType { expected: "an object" }),
+                            None => {}
+                        }
+                    }
+                } else {
+                    errors.push(pointer, ValidationErrorKind::InvalidType { expected: "an object" });
+                }
+            }
+        }
+        Some(_) => errors.push("/paths", ValidationErrorKind::InvalidType { expected: "an object" }),
+        None => {
+            let alternatives = version == SpecVersion::OpenApi31
+                && (root.contains_key("components") || root.contains_key("webhooks"));
+            if !alternatives {
+                errors.push("", ValidationErrorKind::MissingField("paths"));
+            }
+        }
+    }
+
+    check_refs(document, document, &mut String::new(), &mut errors);
+
+    if errors.0.is_empty() {
+        Ok(version)
+    } else {
+        Err(errors.0)
+    }
+}
+
+#[derive(Default)]
+struct Errors(Vec<ValidationError>);
+
+impl Errors {
+    fn push(&mut self, pointer: impl Into<String>, kind: ValidationErrorKind) {
+        self.0.push(ValidationError { pointer: pointer.into(), kind })
+    }
+}
+
+fn check_refs(document: &Value, value: &Value, pointer: &mut String, errors: &mut Errors) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get("$ref") {
+                if reference.starts_with('#') && resolve_local(document, reference).is_none() {
+                    errors.push(format!("{}/$ref", pointer), ValidationErrorKind::UnresolvedRef(reference.clone()));
+                }
+            }
+            for (key, value) in map {
+                let len = pointer.len();
+                pointer.push('/');
+                pointer.push_str(&escape(key));
+                check_refs(document, value, pointer, errors);
+                pointer.truncate(len);
+            }
+        }
+        Value::Array(items) => {
+            for (i, value) in items.iter().enumerate() {
+                let len = pointer.len();
+                pointer.push_str(&format!("/{}", i));
+                check_refs(document, value, pointer, errors);
+                pointer.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Resolves a local reference such as `#/components/schemas/Pet`
+pub fn resolve_local<'a>(document: &'a Value, reference: &str) -> Option<&'a Value> {
+    let fragment = reference.strip_prefix('#')?;
+    document.pointer(&percent_decode(fragment))
+}
+
+/// Escapes a key to be used as a JSON pointer segment
+pub(crate) fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit()
+        {
+            out.push(u8::from_str_radix(&s[i + 1..i + 3], 16).unwrap());
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8(out).unwrap_or_else(|_| s.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn example_spec_is_valid() {
+        let spec = crate::swagger_spec_file!("../examples/openapi.json");
+        assert_eq!(spec.validate(), Ok(SpecVersion::Swagger2));
+    }
+
+    #[test]
+    fn reports_missing_fields() {
+        let errors = validate_value(&json!({"openapi": "3.0.3", "info": {"title": "api"}})).unwrap_err();
+        assert_eq!(errors, vec![
+            ValidationError { pointer: "/info".into(), kind: ValidationErrorKind::MissingField("version") },
+            ValidationError { pointer: "".into(), kind: ValidationErrorKind::MissingField("paths") },
+        ]);
+    }
+
+    #[test]
+    fn reports_unresolved_refs() {
+        let errors = validate_value(&json!({
+            "openapi": "3.1.0",
+            "info": {"title": "api", "version": "1"},
+            "paths": {"/a/{id}": {"get": {
+                "parameters": [{"$ref": "#/components/parameters/Id"}],
+                "responses": {"200": {"$ref": "#/components/responses/Missing"}},
+            }}},
+            "components": {"parameters": {"Id": {"name": "id", "in": "path"}}},
+        })).unwrap_err();
+        assert_eq!(errors, vec![ValidationError {
+            pointer: "/paths/~1a~1{id}/get/responses/200/$ref".into(),
+            kind: ValidationErrorKind::UnresolvedRef("#/components/responses/Missing".into()),
+        }]);
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        let errors = validate_value(&json!({"openapi": "4.0.0"})).unwrap_err();
+        assert_eq!(errors[0].kind, ValidationErrorKind::UnknownVersion(Some("4.0.0".into())));
+    }
+}
@@ -0,0 +1,150 @@
+//! Versioned docs: several versions of a spec served by one swagger-ui mount, with a version selector.
+//!
+//! Each version is served at `{version}/{spec name}` under the mount, e.g. `v2/openapi.json`,
+//! and listed in `Config::urls` so the top bar switches between them. The latest version is listed
+//! first and shown when the page is opened; others can be linked with `index.html?urls.primaryName=v1`.
+
+use std::cmp::Ordering;
+use crate::{Config, Spec, UrlObject};
+
+/// Specs keyed by version, see the module documentation
+#[derive(Debug, Clone, Default)]
+pub struct Versions {
+    specs: Vec<(String, Spec)>,
+    latest: Option<String>,
+}
+
+impl Versions {
+    /// Create an empty set of versions
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `spec` as `version`, which is used as a path segment of its url.
+    /// A spec added again for the same version replaces the previous one.
+    pub fn with_version(mut self, version: impl Into<String>, spec: Spec) -> Self {
+        let version = version.into().trim_matches('/').to_owned();
+        self.specs.retain(|(existing, _)| *existing != version);
+        self.specs.push((version, spec));
+        self
+    }
+
+    /// Marks `version` as the latest one, instead of the greatest version,
+    /// e.g. to keep a pre-release out of the primary spot
+    pub fn with_latest(mut self, version: impl Into<String>) -> Self {
+        self.latest = Some(version.into());
+        self
+    }
+
+    /// Returns the latest version: the one set with `with_latest`, or the greatest one,
+    /// comparing numbers in versions by value (`v10` is after `v9`)
+    pub fn latest(&self) -> Option<&str> {
+        match &self.latest {
+            Some(latest) => self.specs.iter().map(|(version, _)| version.as_str()).find(|version| version == latest),
+            None => self.specs.iter().map(|(version, _)| version.as_str()).max_by(|a, b| natural_cmp(a, b)),
+        }
+    }
+
+    /// Returns versions with their spec, latest first, then from the greatest to the smallest
+    pub fn specs(&self) -> Vec<(&str, &Spec)> {
+        let latest = self.latest();
+        let mut specs = self.specs.iter().map(|(version, spec)| (version.as_str(), spec)).collect::<Vec<_>>();
+        specs.sort_by(|(a, _), (b, _)| (Some(*b) == latest).cmp(&(Some(*a) == latest)).then_with(|| natural_cmp(b, a)));
+        specs
+    }
+
+    /// Returns the path of a version's spec, relative to the mount
+    pub fn spec_path(version: &str, spec: &Spec) -> String {
+        format!("{}/{}", version, spec.name.trim_start_matches('/'))
+    }
+
+    /// Returns the spec served at `path`, relative to the mount
+    pub fn spec(&self, path: &str) -> Option<&Spec> {
+        let path = path.trim_start_matches('/');
+        self.specs.iter()
+            .find(|(version, spec)| path == Self::spec_path(version, spec))
+            .map(|(_, spec)| spec)
+    }
+
+    /// Returns `config` listing the versions in `urls`, with the latest one as primary.
+    /// `base_url` is the url of the mount, with a trailing slash, or empty for urls relative to the page.
+    pub fn config(&self, config: &Config, base_url: &str) -> Config {
+        let mut config = config.clone();
+        config.url = String::new();
+        config.urls = self.specs().into_iter()
+            .map(|(version, spec)| UrlObject::new(version, &format!("{}{}", base_url, Self::spec_path(version, spec))))
+            .collect();
+        config.urls_primary_name = self.latest().unwrap_or_default().to_owned();
+        config
+    }
+}
+
+/// Compares strings by chunks, digits by their value
+pub(crate) fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+    loop {
+        let (chunk_a, rest_a) = split_chunk(a);
+        let (chunk_b, rest_b) = split_chunk(b);
+        let ordering = match (chunk_a.parse::<u64>(), chunk_b.parse::<u64>()) {
+            (Ok(number_a), Ok(number_b)) => number_a.cmp(&number_b),
+            _ => chunk_a.cmp(chunk_b),
+        };
+        if ordering != Ordering::Equal || (chunk_a.is_empty() && chunk_b.is_empty()) {
+            return ordering;
+        }
+        (a, b) = (rest_a, rest_b);
+    }
+}
+
+/// Splits the leading run of digits or non-digits
+fn split_chunk(s: &str) -> (&str, &str) {
+    let digits = s.starts_with(|c: char| c.is_ascii_digit());
+    let end = s.find(|c: char| c.is_ascii_digit() != digits).unwrap_or(s.len());
+    s.split_at(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use crate::Bytes;
+    use super::*;
+
+    fn spec(name: &'static str) -> Spec {
+        Spec { name: Cow::Borrowed(name), content: Bytes::from_static(b"{}") }
+    }
+
+    fn versions() -> Versions {
+        Versions::new()
+            .with_version("v2", spec("openapi.json"))
+            .with_version("v10", spec("openapi.yaml"))
+            .with_version("/v1/", spec("openapi.json"))
+    }
+
+    #[test]
+    fn orders_versions() {
+        let versions = versions();
+        assert_eq!(versions.latest(), Some("v10"));
+        assert_eq!(versions.specs().iter().map(|(version, _)| *version).collect::<Vec<_>>(), ["v10", "v2", "v1"]);
+
+        let versions = versions.with_latest("v2");
+        assert_eq!(versions.latest(), Some("v2"));
+        assert_eq!(versions.specs().iter().map(|(version, _)| *version).collect::<Vec<_>>(), ["v2", "v10", "v1"]);
+        assert_eq!(Versions::new().latest(), None);
+    }
+
+    #[test]
+    fn serves_versioned_urls() {
+        let versions = versions();
+        assert_eq!(versions.spec("/v10/openapi.yaml").unwrap().name, "openapi.yaml");
+        assert!(versions.spec("v1/openapi.json").is_some());
+        assert!(versions.spec("v10/openapi.json").is_none());
+        assert!(versions.spec("openapi.json").is_none());
+
+        let config = versions.config(&Config { url: "openapi.json".to_owned(), ..Config::default() }, "/docs/");
+        let json = serde_json::to_value(&config).unwrap();
+        assert!(json.get("url").is_none());
+        assert_eq!(json["urls.primaryName"], "v10");
+        assert_eq!(json["urls"][0], serde_json::json!({"name": "v10", "url": "/docs/v10/openapi.yaml"}));
+        assert_eq!(json["urls"][2]["url"], "/docs/v1/openapi.json");
+    }
+}